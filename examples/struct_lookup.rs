@@ -7,10 +7,37 @@ static WHEATLEY: wheatley::Wheatley = wheatley::embed_assets! {
     prepend_slash: true,
 };
 
+// Checked to exist at compile time, unlike a lookup through WHEATLEY.get
+static HARDLY_HAUNTED: &wheatley::File = wheatley::asset!(location: "examples/books", path: "hardly_haunted.txt");
+
+#[derive(Debug, wheatley::Wheatley)]
+#[wheatley(file = "examples/config/app.toml")]
+struct AppConfig {
+    port: u16,
+    name: &'static str,
+    features: &'static [&'static str],
+    ratio: f32,
+    released: wheatley::Datetime,
+    database: Database,
+}
+
+#[derive(Debug)]
+struct Database {
+    host: &'static str,
+    port: u16,
+}
+
 fn main() {
     println!("Hello, my name is Sam I am");
 
-    let file = WHEATLEY.get("/examples/books/hardly_haunted.txt");
+    let file = WHEATLEY.get("hardly_haunted.txt").expect("The silo holds hardly_haunted.txt");
+    assert_eq!(file.path, HARDLY_HAUNTED.path);
+    println!("{HARDLY_HAUNTED:#?}");
+
+    let config = AppConfig::load();
+    println!("{} listens on {} with {:?}", config.name, config.port, config.features);
+    println!("Released {} at a ratio of {}", config.released, config.ratio);
+    println!("Reading {}:{}", config.database.host, config.database.port);
     // let mut d = GzDecoder::new(file.contents);
     // let mut s = String::new();
     // d.read_to_string(&mut s).unwrap();
@@ -5,4 +5,16 @@ for codec in br gzip snap zstd; do
 	cargo nextest run -F "$codec" -- "$codec"_compression --exact
 done
 
+cargo nextest run -F owned -- owned
+
+cargo nextest run -F json,yaml,ron --test formats
+
+cargo nextest run -F csv --test csv
+
+cargo nextest run -F serde --test serde
+
+cargo nextest run -F markdown --test markdown
+
+cargo nextest run -F alloc --test templates
+
 cargo nextest run --workspace
@@ -0,0 +1,179 @@
+//! Reader for `.wheat` bundles.
+//!
+//! A bundle is a 144 byte header followed by the tables of a silo. The
+//! header holds the magic bytes, a `u16` format version, the encoding tag
+//! and, for each of the eight tables, its byte offset and byte length as
+//! little-endian `u64`s. Tables appear in this order: level capacities,
+//! bit vector words, ranks, fingerprints, key offsets, keys, content
+//! offsets and contents.
+
+use crate::{read_u32, read_u64, Encoding, Mphf, Wheatley};
+use core::fmt;
+use core::ops::Range;
+
+pub const MAGIC: &[u8; 8] = b"WHEATLEY";
+pub const VERSION: u16 = 1;
+
+const SECTION_TABLE: usize = 16;
+const SECTION_COUNT: usize = 8;
+const HEADER_LEN: usize = SECTION_TABLE + SECTION_COUNT * 16;
+
+const LEVEL_BITS: usize = 0;
+const WORDS: usize = 1;
+const RANKS: usize = 2;
+const FINGERPRINTS: usize = 3;
+const KEY_OFFSETS: usize = 4;
+const KEYS: usize = 5;
+const CONTENT_OFFSETS: usize = 6;
+const CONTENTS: usize = 7;
+
+/// Why a buffer could not be read as a `.wheat` bundle.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BundleError {
+    Truncated,
+    BadMagic,
+    UnsupportedVersion(u16),
+    UnknownEncoding(u8),
+    SectionOutOfBounds,
+    Corrupt(&'static str),
+}
+
+impl fmt::Display for BundleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BundleError::Truncated => write!(f, "bundle is shorter than its header"),
+            BundleError::BadMagic => write!(f, "buffer is not a wheatley bundle"),
+            BundleError::UnsupportedVersion(version) => {
+                write!(f, "bundle format version {version} is not supported")
+            }
+            BundleError::UnknownEncoding(tag) => write!(f, "unknown content encoding {tag}"),
+            BundleError::SectionOutOfBounds => write!(f, "bundle section lies outside the buffer"),
+            BundleError::Corrupt(reason) => write!(f, "corrupt bundle: {reason}"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for BundleError {}
+
+/// Where each table of a bundle lives. Parsing validates the tables once so
+/// lookups never have to.
+#[derive(Clone, Debug)]
+pub(crate) struct Sections {
+    encoding: Encoding,
+    ranges: [Range<usize>; SECTION_COUNT],
+}
+
+impl Sections {
+    pub(crate) fn parse(bytes: &[u8]) -> Result<Self, BundleError> {
+        if bytes.len() < HEADER_LEN {
+            return Err(BundleError::Truncated);
+        }
+
+        if &bytes[..8] != MAGIC {
+            return Err(BundleError::BadMagic);
+        }
+
+        let version = u16::from_le_bytes([bytes[8], bytes[9]]);
+        if version != VERSION {
+            return Err(BundleError::UnsupportedVersion(version));
+        }
+
+        let encoding = Encoding::from_tag(bytes[10]).ok_or(BundleError::UnknownEncoding(bytes[10]))?;
+
+        let mut ranges: [Range<usize>; SECTION_COUNT] = Default::default();
+        for (section, range) in ranges.iter_mut().enumerate() {
+            let table = &bytes[SECTION_TABLE..HEADER_LEN];
+            let offset = usize::try_from(read_u64(table, section * 2));
+            let length = usize::try_from(read_u64(table, section * 2 + 1));
+
+            let (Ok(offset), Ok(length)) = (offset, length) else {
+                return Err(BundleError::SectionOutOfBounds);
+            };
+
+            match offset.checked_add(length) {
+                Some(end) if end <= bytes.len() => *range = offset..end,
+                _ => return Err(BundleError::SectionOutOfBounds),
+            }
+        }
+
+        let sections = Sections { encoding, ranges };
+        sections.validate(bytes)?;
+
+        Ok(sections)
+    }
+
+    pub(crate) fn silo<'a, K: ?Sized>(&self, bytes: &'a [u8]) -> Wheatley<'a, K> {
+        let section = |index: usize| &bytes[self.ranges[index].clone()];
+
+        Wheatley::new(
+            Mphf::new(section(LEVEL_BITS), section(WORDS), section(RANKS)),
+            self.encoding,
+            section(FINGERPRINTS),
+            section(KEYS),
+            section(KEY_OFFSETS),
+            section(CONTENTS),
+            section(CONTENT_OFFSETS),
+        )
+    }
+
+    fn validate(&self, bytes: &[u8]) -> Result<(), BundleError> {
+        let section = |index: usize| &bytes[self.ranges[index].clone()];
+
+        for (index, width) in [
+            (LEVEL_BITS, 8),
+            (WORDS, 8),
+            (RANKS, 8),
+            (FINGERPRINTS, 8),
+            (KEY_OFFSETS, 4),
+            (CONTENT_OFFSETS, 4),
+        ] {
+            if section(index).len() % width != 0 {
+                return Err(BundleError::Corrupt("table length is not a whole number of items"));
+            }
+        }
+
+        let level_bits = section(LEVEL_BITS);
+        let mut words = 0;
+        let mut ranks = 0;
+        for level in 0..level_bits.len() / 8 {
+            let bits = read_u64(level_bits, level);
+            if bits == 0 {
+                return Err(BundleError::Corrupt("hash level is empty"));
+            }
+
+            let level_words = bits.div_ceil(64) as usize;
+            words += level_words;
+            ranks += level_words.div_ceil(8);
+        }
+
+        if section(WORDS).len() != words * 8 || section(RANKS).len() != ranks * 8 {
+            return Err(BundleError::Corrupt("hash levels do not match their tables"));
+        }
+
+        let entries = section(FINGERPRINTS).len() / 8;
+        check_offsets(section(KEY_OFFSETS), entries, section(KEYS).len())?;
+        check_offsets(section(CONTENT_OFFSETS), entries, section(CONTENTS).len())
+    }
+}
+
+fn check_offsets(offsets: &[u8], entries: usize, pool_len: usize) -> Result<(), BundleError> {
+    if offsets.len() / 4 != entries + 1 || read_u32(offsets, 0) != 0 {
+        return Err(BundleError::Corrupt("offsets do not cover every entry"));
+    }
+
+    let mut previous = 0;
+    for position in 1..=entries {
+        let offset = read_u32(offsets, position) as usize;
+        if offset < previous {
+            return Err(BundleError::Corrupt("offsets are not ascending"));
+        }
+        previous = offset;
+    }
+
+    if previous != pool_len {
+        return Err(BundleError::Corrupt("offsets do not end at the pool length"));
+    }
+
+    Ok(())
+}
@@ -1,20 +1,55 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+mod bundle;
+mod datetime;
 mod mphf;
-use std::hash::Hash;
+#[cfg(feature = "owned")]
+mod owned;
+use core::borrow::Borrow;
+use core::fmt;
+use core::hash::Hash;
+use core::marker::PhantomData;
 
+pub use bundle::BundleError;
+pub use datetime::{Date, Datetime, Offset, Time};
+pub use mphf::bbhash::Mphf;
 pub use mphf::bitvector::BitVector;
+#[cfg(feature = "owned")]
+pub use owned::{OwnedWheatley, Options};
 pub use wheatley_macro::*;
 
+// Derived enums implement serde's traits through this path so that callers
+// need no direct dependency on serde
+#[cfg(feature = "serde")]
+#[doc(hidden)]
+pub use serde;
+
+// Template methods build their `String` through this path, which works for
+// `no_std` callers with an allocator as well
+#[cfg(feature = "alloc")]
+#[doc(hidden)]
+pub extern crate alloc;
+
+#[derive(Clone, Copy)]
 pub struct File<'a> {
     pub path: &'a [u8],
     pub contents: &'a [u8],
 }
 
-impl std::fmt::Debug for File<'_> {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let path = String::from_utf8_lossy(self.path);
-        let content = String::from_utf8(self.contents.to_vec())
-            .map(|content| content.chars().take(30).collect::<String>() + "...")
-            .unwrap_or_else(|_| String::from("---snipped non-utf8 content---"));
+impl fmt::Debug for File<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let path = core::str::from_utf8(self.path).unwrap_or("---non-utf8 path---");
+        let content = match core::str::from_utf8(self.contents) {
+            Ok(content) => {
+                let end = content
+                    .char_indices()
+                    .nth(30)
+                    .map_or(content.len(), |(index, _)| index);
+
+                format_args!("{}...", &content[..end])
+            }
+            Err(_) => format_args!("---snipped non-utf8 content---"),
+        };
 
         f.debug_struct("File")
             .field("path", &path)
@@ -29,47 +64,296 @@ impl<'a> File<'a> {
     }
 }
 
-#[derive(Debug)]
-pub enum Entry<'a> {
-    File(File<'a>),
+/// The position of a file among the entries of a silo, read through
+/// [`Wheatley::file`] without hashing. Nothing ties a position to the silo it
+/// came from, so the constants `embed_assets!` generates with
+/// `constants: assets` wrap it in a handle type only their silo reads.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Handle {
+    position: usize,
+}
+
+impl Handle {
+    pub const fn new(position: usize) -> Self {
+        Self { position }
+    }
+
+    /// The index of the file among the entries of its silo.
+    pub const fn position(self) -> usize {
+        self.position
+    }
 }
 
-pub struct Wheatley<'a> {
-    entries: &'a [Entry<'a>],
-    mphf: mphf::bbhash::Mphf<'a>,
+/// Returned when a name matches none of the files behind a derived enum.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ParseAssetError {
+    target: &'static str,
 }
 
-impl std::fmt::Debug for Wheatley<'_> {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.debug_struct("File")
-            .field("entries", &self.entries)
+impl ParseAssetError {
+    pub const fn new(target: &'static str) -> Self {
+        Self { target }
+    }
+}
+
+impl fmt::Display for ParseAssetError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "no file of {} matches the given name", self.target)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ParseAssetError {}
+
+/// How the contents of every file in a silo are encoded.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Encoding {
+    #[default]
+    Identity,
+    Gzip,
+    Brotli,
+    Snappy,
+    Zstd,
+}
+
+impl Encoding {
+    fn from_tag(tag: u8) -> Option<Self> {
+        match tag {
+            0 => Some(Encoding::Identity),
+            1 => Some(Encoding::Gzip),
+            2 => Some(Encoding::Brotli),
+            3 => Some(Encoding::Snappy),
+            4 => Some(Encoding::Zstd),
+            _ => None,
+        }
+    }
+}
+
+/// Lookup and iteration shared by embedded and runtime silos, so code can
+/// take any `S: Silo` without caring where the assets came from.
+pub trait Silo {
+    /// What files are looked up by: `str` for paths, or e.g. `u32` for
+    /// silos keyed by a pattern such as `sprite_{id}.png`.
+    type Key: Hash + ?Sized;
+
+    fn get<Q: Borrow<Self::Key>>(&self, key: Q) -> Option<File<'_>>;
+
+    fn iter(&self) -> Iter<'_, Self::Key>;
+
+    fn len(&self) -> usize;
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// A silo of assets laid out as a few flat tables.
+///
+/// Every path lives in one key pool and every asset in one contents blob.
+/// Entry `i` spans `key_offsets[i]..key_offsets[i + 1]` of the pool and
+/// `content_offsets[i]..content_offsets[i + 1]` of the blob, which keeps
+/// the number of relocations constant no matter how many files are embedded.
+///
+/// Fingerprints are little-endian `u64`s and offsets little-endian `u32`s,
+/// so the same tables can be read in place from a `.wheat` bundle.
+///
+/// Files are looked up by `K`, which is the path unless the silo was keyed
+/// by a file name pattern. Integer keys are hashed as little-endian bytes,
+/// so a silo built on one machine works on targets of either byte order.
+pub struct Wheatley<'a, K: ?Sized = str> {
+    key: PhantomData<fn(&K)>,
+    mphf: Mphf<'a>,
+    encoding: Encoding,
+    fingerprints: &'a [u8],
+    keys: &'a [u8],
+    key_offsets: &'a [u8],
+    contents: &'a [u8],
+    content_offsets: &'a [u8],
+}
+
+impl<K: ?Sized> Clone for Wheatley<'_, K> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<K: ?Sized> Copy for Wheatley<'_, K> {}
+
+impl<K: ?Sized> fmt::Debug for Wheatley<'_, K> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        struct Entries<'a, K: ?Sized>(Wheatley<'a, K>);
+
+        impl<K: ?Sized> fmt::Debug for Entries<'_, K> {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.debug_list().entries(self.0.iter()).finish()
+            }
+        }
+
+        f.debug_struct("Wheatley")
+            .field("entries", &Entries(*self))
             .finish()
     }
 }
 
+impl<'a, K: ?Sized> Wheatley<'a, K> {
+    pub const fn new(
+        mphf: Mphf<'a>,
+        encoding: Encoding,
+        fingerprints: &'a [u8],
+        keys: &'a [u8],
+        key_offsets: &'a [u8],
+        contents: &'a [u8],
+        content_offsets: &'a [u8],
+    ) -> Self {
+        Self {
+            key: PhantomData,
+            mphf,
+            encoding,
+            fingerprints,
+            keys,
+            key_offsets,
+            contents,
+            content_offsets,
+        }
+    }
+
+    /// Read a silo keyed by something other than paths out of a `.wheat`
+    /// bundle. Bundles don't record their key type, so `K` must match the
+    /// one the bundle was written with.
+    pub fn from_keyed_bundle(bytes: &'a [u8]) -> Result<Self, BundleError> {
+        bundle::Sections::parse(bytes).map(|sections| sections.silo(bytes))
+    }
+
+    pub fn encoding(&self) -> Encoding {
+        self.encoding
+    }
+
+    pub fn iter(&self) -> Iter<'a, K> {
+        Iter {
+            silo: *self,
+            position: 0,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.fingerprints.len() / 8
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The file at the position of `handle`. A handle taken from another
+    /// silo reads whichever file is at its position here. Panics if the silo
+    /// has no entry at that position.
+    pub fn file(&self, handle: Handle) -> File<'a> {
+        assert!(handle.position < self.len(), "The silo has no entry at the position of the handle");
+
+        self.entry(handle.position)
+    }
+
+    fn entry(&self, position: usize) -> File<'a> {
+        let path = slice(self.keys, self.key_offsets, position);
+        let contents = slice(self.contents, self.content_offsets, position);
+
+        File::new(path, contents)
+    }
+}
+
 impl<'a> Wheatley<'a> {
-    pub const fn new(entries: &'a [Entry], mphf_state: &'a [(BitVector<'a>, &'a [u64])]) -> Self {
-        let mphf = mphf::bbhash::Mphf::new(mphf_state);
+    /// Read a silo out of a `.wheat` bundle without copying it.
+    ///
+    /// `bytes` may come from `include_bytes!`, a file read into memory or
+    /// an mmap; no alignment is required.
+    pub fn from_bundle(bytes: &'a [u8]) -> Result<Self, BundleError> {
+        Self::from_keyed_bundle(bytes)
+    }
+}
+
+impl<'a, K: Hash + ?Sized> Wheatley<'a, K> {
+    pub fn get<Q: Borrow<K>>(&self, key: Q) -> Option<File<'a>> {
+        let key = key.borrow();
+        let position = self.mphf.hash(key)? as usize;
+
+        if position >= self.len()
+            || read_u64(self.fingerprints, position) != mphf::bbhash::fingerprint(key)
+        {
+            return None;
+        }
+
+        Some(self.entry(position))
+    }
+}
+
+impl<K: Hash + ?Sized> Silo for Wheatley<'_, K> {
+    type Key = K;
+
+    fn get<Q: Borrow<K>>(&self, key: Q) -> Option<File<'_>> {
+        Wheatley::get(self, key)
+    }
+
+    fn iter(&self) -> Iter<'_, K> {
+        Wheatley::iter(self)
+    }
 
-        Self { mphf, entries }
+    fn len(&self) -> usize {
+        Wheatley::len(self)
     }
+}
+
+impl<'a, K: ?Sized> IntoIterator for &Wheatley<'a, K> {
+    type Item = File<'a>;
+    type IntoIter = Iter<'a, K>;
 
-    pub fn get<T: Hash + core::fmt::Debug>(&self, key: T) -> Option<&File> {
-        println!("we should be here");
-        println!("{:#?}", self.entries);
-        println!("Right after printing");
-        let entry = self
-            .mphf
-            .hash(&key)
-            .inspect(|s| println!("Got milk: {s:#?}"))
-            .map(|position| &self.entries[position as usize])?;
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
 
-        println!("why not here?");
+/// Iterator over the files of a silo in hash order.
+pub struct Iter<'a, K: ?Sized = str> {
+    silo: Wheatley<'a, K>,
+    position: usize,
+}
 
-        println!("What we got");
+impl<'a, K: ?Sized> Iterator for Iter<'a, K> {
+    type Item = File<'a>;
 
-        match entry {
-            Entry::File(file) => Some(file),
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.position == self.silo.len() {
+            return None;
         }
+
+        let file = self.silo.entry(self.position);
+        self.position += 1;
+
+        Some(file)
     }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.silo.len() - self.position;
+
+        (remaining, Some(remaining))
+    }
+}
+
+impl<K: ?Sized> ExactSizeIterator for Iter<'_, K> {}
+
+fn slice<'a>(pool: &'a [u8], offsets: &[u8], position: usize) -> &'a [u8] {
+    let start = read_u32(offsets, position) as usize;
+    let end = read_u32(offsets, position + 1) as usize;
+
+    &pool[start..end]
+}
+
+pub(crate) fn read_u64(bytes: &[u8], index: usize) -> u64 {
+    let word = &bytes[index * 8..index * 8 + 8];
+
+    u64::from_le_bytes(word.try_into().unwrap())
+}
+
+pub(crate) fn read_u32(bytes: &[u8], index: usize) -> u32 {
+    let word = &bytes[index * 4..index * 4 + 4];
+
+    u32::from_le_bytes(word.try_into().unwrap())
 }
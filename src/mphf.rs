@@ -29,16 +29,81 @@ pub mod bbhash {
 
     use crate::mphf::bitvector::BitVector;
 
-    use std::fmt::Debug;
-    use std::hash::Hash;
-    use std::hash::Hasher;
+    use core::hash::Hash;
+    use core::hash::Hasher;
+
+    // Seed used to fingerprint keys. Level seeds are all powers of four,
+    // so an odd seed never collides with them.
+    const FINGERPRINT_SEED: u64 = 0x9E37_79B9_7F4A_7C15;
+
+    /// Feeds integers to wyhash as little-endian bytes, where `Hash` would
+    /// write them in the byte order of the machine. A silo built on one
+    /// machine then finds its keys on a target of the other endianness.
+    /// `usize` and `isize` are widened to 64 bits for the same reason.
+    struct LittleEndian(wyhash::WyHash);
+
+    impl LittleEndian {
+        fn with_seed(seed: u64) -> Self {
+            Self(wyhash::WyHash::with_seed(seed))
+        }
+    }
+
+    impl Hasher for LittleEndian {
+        fn finish(&self) -> u64 {
+            self.0.finish()
+        }
+
+        fn write(&mut self, bytes: &[u8]) {
+            self.0.write(bytes)
+        }
+
+        fn write_u16(&mut self, i: u16) {
+            self.write(&i.to_le_bytes())
+        }
+
+        fn write_u32(&mut self, i: u32) {
+            self.write(&i.to_le_bytes())
+        }
+
+        fn write_u64(&mut self, i: u64) {
+            self.write(&i.to_le_bytes())
+        }
+
+        fn write_u128(&mut self, i: u128) {
+            self.write(&i.to_le_bytes())
+        }
+
+        fn write_usize(&mut self, i: usize) {
+            self.write(&(i as u64).to_le_bytes())
+        }
+
+        fn write_i16(&mut self, i: i16) {
+            self.write(&i.to_le_bytes())
+        }
+
+        fn write_i32(&mut self, i: i32) {
+            self.write(&i.to_le_bytes())
+        }
+
+        fn write_i64(&mut self, i: i64) {
+            self.write(&i.to_le_bytes())
+        }
+
+        fn write_i128(&mut self, i: i128) {
+            self.write(&i.to_le_bytes())
+        }
+
+        fn write_isize(&mut self, i: isize) {
+            self.write(&(i as i64).to_le_bytes())
+        }
+    }
 
     fn fold(v: u64) -> u32 {
         ((v & 0xFFFFFFFF) as u32) ^ ((v >> 32) as u32)
     }
 
     fn hash_with_seed<T: Hash + ?Sized>(iter: u64, v: &T) -> u64 {
-        let mut state = wyhash::WyHash::with_seed(1 << (iter + iter));
+        let mut state = LittleEndian::with_seed(1 << (iter + iter));
         v.hash(&mut state);
         state.finish()
     }
@@ -56,38 +121,48 @@ pub mod bbhash {
         // https://lemire.me/blog/2016/06/27/a-fast-alternative-to-the-modulo-reduction/
         if n < (1 << 32) {
             let h = hash_with_seed32(iter, v);
-            fastmod(h, n as u32) as u64
+            fastmod(h, n as u32)
         } else {
             let h = hash_with_seed(iter, v);
             h % n
         }
     }
 
-    /// A minimal perfect hash function over a set of objects of type `T`.
-    #[derive(Clone, Debug)]
+    /// Hash `item` with a seed independent of the levels so a lookup can
+    /// confirm the entry it landed on belongs to the key.
+    pub fn fingerprint<T: Hash + ?Sized>(item: &T) -> u64 {
+        let mut state = LittleEndian::with_seed(FINGERPRINT_SEED);
+        item.hash(&mut state);
+        state.finish()
+    }
+
+    /// A minimal perfect hash function over a set of keys.
+    ///
+    /// The levels are stored flat: `level_bits` holds the capacity of each
+    /// level, `words` the concatenated bit vectors and `ranks` the
+    /// concatenated rank tables (one rank per 8 words of a level). Every
+    /// table is a little-endian `u64` array.
+    #[derive(Clone, Copy, Debug)]
     pub struct Mphf<'a> {
-        // bitvecs: Box<[(BitVector<'a>, Box<[u64]>)]>,
-        // phantom: PhantomData<T>,
-        bit_vectors_with_ranks: &'a [(BitVector<'a>, &'a [u64])],
+        level_bits: &'a [u8],
+        words: &'a [u8],
+        ranks: &'a [u8],
     }
 
     impl<'a> Mphf<'a> {
-        pub const fn new(bit_vectors_with_ranks: &'a [(BitVector<'a>, &'a [u64])]) -> Mphf {
-            // Mphf { bitvecs: Box::new([]), phantom: PhantomData }
+        pub const fn new(level_bits: &'a [u8], words: &'a [u8], ranks: &'a [u8]) -> Mphf<'a> {
             Mphf {
-                bit_vectors_with_ranks,
+                level_bits,
+                words,
+                ranks,
             }
         }
 
-        fn get_rank(&self, hash: u64, i: usize) -> u64 {
+        fn get_rank(bv: &BitVector, ranks: &[u8], hash: u64) -> u64 {
             let idx = hash as usize;
-            let (bv, ranks) = self
-                .bit_vectors_with_ranks
-                .get(i)
-                .expect("that level doesn't exist");
 
             // Last pre-computed rank
-            let mut rank = ranks[idx / 512];
+            let mut rank = crate::read_u64(ranks, idx / 512);
 
             // Add rank of intervening words
             for j in (idx / 64) & !7..idx / 64 {
@@ -96,24 +171,31 @@ pub mod bbhash {
 
             // Add rank of final word up to hash
             let final_word = bv.get_word(idx / 64);
-            if idx % 64 > 0 {
+            if !idx.is_multiple_of(64) {
                 rank += (final_word << (64 - (idx % 64))).count_ones() as u64;
             }
             rank
         }
 
-        /// Compute the hash value of `item`. This method should only be used
-        /// with items known to be in construction set. Use `try_hash` if you cannot
-        /// guarantee that `item` was in the construction set. If `item` was not present
-        /// in the construction set this function may panic.
-        pub fn hash<T: Hash + Debug>(&self, item: &T) -> Option<u64> {
-            for i in 0..self.bit_vectors_with_ranks.len() {
-                let (bv, _) = &self.bit_vectors_with_ranks[i];
-                let hash = hashmod(i as u64, item, bv.capacity());
+        /// Compute the hash value of `item`. Items outside of the construction
+        /// set either return `None` or the position of another item, so callers
+        /// must confirm the result, e.g. with a [`fingerprint`].
+        pub fn hash<T: Hash + ?Sized>(&self, item: &T) -> Option<u64> {
+            let mut word_offset = 0;
+            let mut rank_offset = 0;
+
+            for i in 0..self.level_bits.len() / 8 {
+                let bits = crate::read_u64(self.level_bits, i);
+                let num_words = bits.div_ceil(64) as usize;
+                let bv = BitVector::new(bits, &self.words[word_offset * 8..(word_offset + num_words) * 8]);
+                let hash = hashmod(i as u64, item, bits);
 
                 if bv.contains(hash) {
-                    return Some(self.get_rank(hash, i));
+                    return Some(Self::get_rank(&bv, &self.ranks[rank_offset * 8..], hash));
                 }
+
+                word_offset += num_words;
+                rank_offset += num_words.div_ceil(8);
             }
 
             None
@@ -303,18 +385,24 @@ pub mod bitvector {
 
     /// Bitvector
     #[derive(Debug)]
+    ///
+    /// The words are stored as little-endian bytes so a bit vector can be
+    /// read straight out of a bundle without any alignment requirement.
     pub struct BitVector<'a> {
         bits: u64,
 
-        vector: &'a [u64],
+        vector: &'a [u8],
     }
 
     impl<'a> BitVector<'a> {
-        pub const fn from_embedded_state(embedded_state: (u64, &'a [u64])) -> Self {
-            let (bits, vector) = embedded_state;
+        pub const fn new(bits: u64, vector: &'a [u8]) -> Self {
             BitVector { bits, vector }
         }
 
+        fn words(&self) -> impl Iterator<Item = Word> + 'a {
+            self.vector.chunks_exact(8).map(|word| crate::read_u64(word, 0))
+        }
+
         /// return if this set is empty
         ///
         /// if set does not contain any elements, return true;
@@ -323,13 +411,12 @@ pub mod bitvector {
         /// This method is averagely faster than `self.len() > 0`.
         #[allow(dead_code)]
         pub fn is_empty(&self) -> bool {
-            return self.vector.iter().all(|x| *x == 0);
+            self.vector.iter().all(|x| *x == 0)
         }
 
         /// the number of elements in set
         pub fn len(&self) -> u64 {
-            self.vector
-                .iter()
+            self.words()
                 .fold(0u64, |x0, x| x0 + x.count_ones() as u64)
         }
 
@@ -355,45 +442,14 @@ pub mod bitvector {
             //
             // self.vector.as_slice()[0 .. word] == other.vector.as_slice[0 .. word]
             //
-            self.vector
-                .iter()
-                .zip(other.vector.iter())
+            self.words()
+                .zip(other.words())
                 .take(word as usize)
                 .all(|(s1, s2)| s1 == s2)
                 && (self.get_word(word as usize) << (63 - offset))
                     == (other.get_word(word as usize) << (63 - offset))
         }
 
-        /// insert a new element to set
-        ///
-        /// If value is inserted, return true,
-        /// if value already exists in set, return false.
-        ///
-        /// Insert, remove and contains do not do bound check.
-
-        /// insert a new element synchronously.
-        /// requires &mut self, but doesn't use
-        /// atomic instructions so may be faster
-        /// than `insert()`.
-        ///
-        /// If value is inserted, return true,
-        /// if value already exists in set, return false.
-        ///
-        /// Insert, remove and contains do not do bound check.
-        // #[inline]
-
-        /// remove an element from set
-        ///
-        /// If value is removed, return true,
-        /// if value doesn't exist in set, return false.
-        ///
-        /// Insert, remove and contains do not do bound check.
-
-        /// import elements from another bitvector
-        ///
-        /// If any new value is inserted, return true,
-        /// else return false.
-
         /// the max number of elements can be inserted into set
         pub fn capacity(&self) -> u64 {
             self.bits
@@ -401,17 +457,17 @@ pub mod bitvector {
 
         #[inline]
         pub fn get_word(&self, word: usize) -> u64 {
-            self.vector[word]
+            crate::read_u64(self.vector, word)
         }
 
         pub fn num_words(&self) -> usize {
-            self.vector.len()
+            self.vector.len() / 8
         }
 
         /// Return a iterator of the set element in the bitvector,
         pub fn iter(&self) -> BitVectorIter<'_> {
             BitVectorIter {
-                iter: self.vector.iter(),
+                iter: self.vector.chunks_exact(8),
                 current: 0,
                 idx: 0,
                 size: self.bits,
@@ -421,7 +477,7 @@ pub mod bitvector {
 
     /// Iterator for BitVector
     pub struct BitVectorIter<'a> {
-        iter: ::std::slice::Iter<'a, Word>,
+        iter: ::core::slice::ChunksExact<'a, u8>,
         current: u64,
         idx: u64,
         size: u64,
@@ -435,7 +491,7 @@ pub mod bitvector {
             }
             while self.current == 0 {
                 self.current = if let Some(_i) = self.iter.next() {
-                    let i = *_i;
+                    let i = crate::read_u64(_i, 0);
                     if i == 0 {
                         self.idx += 64;
                         continue;
@@ -456,7 +512,7 @@ pub mod bitvector {
     }
 
     fn u64s(elements: u64) -> u64 {
-        (elements + 63) / 64
+        elements.div_ceil(64)
     }
 
     fn word_offset(index: u64) -> (u64, u64) {
@@ -0,0 +1,94 @@
+use crate::bundle::Sections;
+use crate::{BundleError, File, Iter, Silo, Wheatley};
+use std::borrow::Borrow;
+use std::io;
+use std::path::Path;
+use wheatley_build::Layout;
+
+pub use wheatley_build::Options;
+
+/// A silo built at runtime which owns its tables.
+///
+/// It holds a `.wheat` bundle, laid out exactly like the silos
+/// `embed_assets!` produces, so lookups run through the same [`Wheatley`]
+/// code.
+pub struct OwnedWheatley {
+    bytes: Vec<u8>,
+    sections: Sections,
+}
+
+impl std::fmt::Debug for OwnedWheatley {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.as_wheatley().fmt(f)
+    }
+}
+
+impl OwnedWheatley {
+    /// Gather every file below `location`, keyed the same way as
+    /// `embed_assets!` would key them.
+    pub fn from_dir<P: AsRef<Path>>(location: P, options: &Options) -> io::Result<Self> {
+        let assets = wheatley_build::gather(location, options)?
+            .into_iter()
+            .collect::<Vec<(String, Vec<u8>)>>();
+
+        Ok(Self::from_bundle(Layout::from_assets(assets).to_bundle())
+            .expect("A freshly written bundle is valid"))
+    }
+
+    /// Take ownership of a `.wheat` bundle, e.g. one downloaded as an update.
+    pub fn from_bundle<B: Into<Vec<u8>>>(bytes: B) -> Result<Self, BundleError> {
+        let bytes = bytes.into();
+        let sections = Sections::parse(&bytes)?;
+
+        Ok(Self { bytes, sections })
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    pub fn as_wheatley(&self) -> Wheatley<'_> {
+        self.sections.silo(&self.bytes)
+    }
+
+    pub fn get<Q: Borrow<str>>(&self, key: Q) -> Option<File<'_>> {
+        self.as_wheatley().get(key)
+    }
+
+    pub fn iter(&self) -> Iter<'_> {
+        self.as_wheatley().iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.as_wheatley().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl Silo for OwnedWheatley {
+    type Key = str;
+
+    fn get<Q: Borrow<str>>(&self, key: Q) -> Option<File<'_>> {
+        OwnedWheatley::get(self, key)
+    }
+
+    fn iter(&self) -> Iter<'_> {
+        OwnedWheatley::iter(self)
+    }
+
+    fn len(&self) -> usize {
+        OwnedWheatley::len(self)
+    }
+}
+
+impl<'a> IntoIterator for &'a OwnedWheatley {
+    type Item = File<'a>;
+    type IntoIter = Iter<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
@@ -0,0 +1,26 @@
+static HARDLY_HAUNTED: &wheatley::File = wheatley::asset!(location: "examples/books", path: "hardly_haunted.txt");
+
+#[test]
+fn embed_single_file() {
+    let expected_contents = std::fs::read_to_string("examples/books/hardly_haunted.txt").unwrap();
+
+    assert_eq!(HARDLY_HAUNTED.path, b"hardly_haunted.txt");
+    assert_eq!(HARDLY_HAUNTED.contents, expected_contents.as_bytes());
+}
+
+#[test]
+fn key_file_like_the_silo() {
+    let silo: wheatley::Wheatley = wheatley::embed_assets! {
+        location: "examples/books",
+        prefix: "/books/",
+    };
+    let file: &'static wheatley::File = wheatley::asset! {
+        location: "examples/books",
+        prefix: "/books/",
+        path: "/books/theodor_seuss_geisel/dr_seuss/cat_in_the_hat.txt",
+    };
+
+    let looked_up = silo.get("/books/theodor_seuss_geisel/dr_seuss/cat_in_the_hat.txt").unwrap();
+
+    assert_eq!(file.path, looked_up.path);
+}
@@ -0,0 +1,44 @@
+wheatley::asset_enum! {
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    pub enum Books { location: "examples/books" }
+}
+
+#[test]
+fn declare_variant_per_file() {
+    assert_eq!(Books::try_from("hardly_haunted"), Ok(Books::HardlyHaunted));
+    assert_eq!("hug_a_cactus".parse::<Books>(), Ok(Books::HugACactus));
+}
+
+#[test]
+fn read_variant_file() {
+    let expected_contents = std::fs::read_to_string("examples/books/hug_a_cactus.txt").unwrap();
+
+    assert_eq!(Books::HugACactus.contents(), expected_contents);
+    assert_eq!(Books::HugACactus.path(), "hug_a_cactus.txt");
+}
+
+#[test]
+fn declare_subdirectories_as_nested_enums() {
+    assert_eq!(books::SuzanneLang::GrumpyMonkey.path(), "suzanne_lang/grumpy_monkey.txt");
+    assert_eq!(
+        Books::try_from("theodor_seuss_geisel/dr_seuss/cat_in_the_hat"),
+        Ok(Books::TheodorSeussGeisel(books::TheodorSeussGeisel::DrSeuss(
+            books::theodor_seuss_geisel::DrSeuss::CatInTheHat
+        )))
+    );
+    assert!(books::theodor_seuss_geisel::TheoLesieg::WackyWednesday
+        .contents()
+        .contains("abnormal day"));
+    assert_eq!(Books::COUNT, 8);
+}
+
+wheatley::asset_enum! {
+    #[derive(Debug, PartialEq)]
+    enum Cars { location: "examples/cars" }
+}
+
+#[test]
+fn read_variant_properties() {
+    assert_eq!(Cars::ModelY.make(), "Tesla");
+    assert_eq!(Cars::Civic.trim(), Some("Si"));
+}
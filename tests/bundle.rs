@@ -0,0 +1,114 @@
+use wheatley::{embed_assets, BundleError, Wheatley};
+use wheatley_build::{Encoding, Layout, Options};
+
+/// Compress `contents` as `embed_assets!` does with the enabled codec
+/// feature, and say how it is encoded.
+#[allow(unreachable_code)]
+fn compress(contents: Vec<u8>) -> (Encoding, Vec<u8>) {
+    #[cfg(feature = "gzip")]
+    {
+        use std::io::Write;
+
+        let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder.write_all(&contents).unwrap();
+        return (Encoding::Gzip, encoder.finish().unwrap());
+    }
+    #[cfg(feature = "br")]
+    {
+        let mut compressed = vec![];
+        brotli::enc::BrotliCompress(&mut contents.as_slice(), &mut compressed, &Default::default()).unwrap();
+        return (Encoding::Brotli, compressed);
+    }
+    #[cfg(feature = "zstd")]
+    {
+        return (Encoding::Zstd, zstd::encode_all(contents.as_slice(), 0).unwrap());
+    }
+    #[cfg(feature = "snap")]
+    {
+        use std::io::Write;
+
+        let mut encoder = snap::write::FrameEncoder::new(vec![]);
+        encoder.write_all(&contents).unwrap();
+        return (Encoding::Snappy, encoder.into_inner().unwrap());
+    }
+
+    (Encoding::Identity, contents)
+}
+
+fn write_books_bundle() -> Vec<u8> {
+    let mut encoding = Encoding::Identity;
+    let assets = wheatley_build::gather("examples/books", &Options::default())
+        .unwrap()
+        .into_iter()
+        .map(|(path, contents)| {
+            let (used, compressed) = compress(contents);
+            encoding = used;
+            (path, compressed)
+        })
+        .collect::<Vec<(String, Vec<u8>)>>();
+
+    let mut layout = Layout::from_assets(assets);
+    layout.encoding = encoding;
+    layout.to_bundle()
+}
+
+#[test]
+fn bundle_reads_like_embedded_silo() {
+    let bundle = write_books_bundle();
+    let bundled = Wheatley::from_bundle(&bundle).unwrap();
+    let embedded: Wheatley = embed_assets! {
+        location: "examples/books",
+    };
+
+    assert_eq!(bundled.len(), embedded.len());
+    assert_eq!(bundled.encoding(), embedded.encoding());
+
+    for file in embedded.iter() {
+        let found = bundled.get(std::str::from_utf8(file.path).unwrap()).unwrap();
+
+        assert_eq!(found.contents, file.contents);
+    }
+    assert!(bundled.get("green_eggs_and_ham.txt").is_none());
+}
+
+#[test]
+fn bundle_needs_no_alignment() {
+    let bundle = write_books_bundle();
+    let mut shifted = vec![0];
+    shifted.extend_from_slice(&bundle);
+
+    let bundled = Wheatley::from_bundle(&shifted[1..]).unwrap();
+
+    assert!(bundled
+        .get("theodor_seuss_geisel/dr_seuss/cat_in_the_hat.txt")
+        .is_some());
+}
+
+#[test]
+fn reject_malformed_bundles() {
+    let bundle = write_books_bundle();
+
+    assert_eq!(
+        Wheatley::from_bundle(&bundle[..20]).unwrap_err(),
+        BundleError::Truncated
+    );
+
+    let mut bad_magic = bundle.clone();
+    bad_magic[0] = b'w';
+    assert_eq!(
+        Wheatley::from_bundle(&bad_magic).unwrap_err(),
+        BundleError::BadMagic
+    );
+
+    let mut future_version = bundle.clone();
+    future_version[8] = 9;
+    assert_eq!(
+        Wheatley::from_bundle(&future_version).unwrap_err(),
+        BundleError::UnsupportedVersion(9)
+    );
+
+    assert_eq!(
+        Wheatley::from_bundle(&bundle[..bundle.len() - 1]).unwrap_err(),
+        BundleError::SectionOutOfBounds
+    );
+}
@@ -72,3 +72,31 @@ fn read_loaction_given_windows_path() {
     )
 }
 
+
+#[test]
+fn read_nested_asset_with_prefix() {
+    let (key, expected_contents) = read_random_book("/");
+
+    let wheatley: wheatley::Wheatley = embed_assets! {
+        location: "examples/books",
+        prefix: "examples/books/",
+    };
+    let file = wheatley.get(key.as_str()).unwrap();
+
+    assert_eq!(file.path, key.as_bytes());
+    assert_eq!(
+        String::from_utf8(file.contents.to_vec()).unwrap(),
+        expected_contents
+    )
+}
+
+#[test]
+fn unknown_key_is_absent() {
+    assert!(!confirm_book_in_library("green_eggs_and_ham.txt"));
+
+    let wheatley: wheatley::Wheatley = embed_assets! {
+        location: "examples/books",
+    };
+
+    assert!(wheatley.get("green_eggs_and_ham.txt").is_none());
+}
@@ -0,0 +1,43 @@
+wheatley::embed_assets! {
+    location: "examples/books",
+    constants: books,
+}
+
+use books::theodor_seuss_geisel::dr_seuss;
+
+#[test]
+fn read_file_through_constant() {
+    let file = dr_seuss::CAT_IN_THE_HAT_TXT.file();
+
+    assert_eq!(file.path, b"theodor_seuss_geisel/dr_seuss/cat_in_the_hat.txt");
+
+    // Codec features embed the contents compressed
+    if books::SILO.encoding() == wheatley::Encoding::Identity {
+        let expected_contents =
+            std::fs::read_to_string("examples/books/theodor_seuss_geisel/dr_seuss/cat_in_the_hat.txt").unwrap();
+
+        assert_eq!(file.contents, expected_contents.as_bytes());
+    }
+}
+
+#[test]
+fn resolve_the_file_a_lookup_finds() {
+    let looked_up = books::SILO.get("hardly_haunted.txt").unwrap();
+
+    assert_eq!(books::HARDLY_HAUNTED_TXT.file().contents, looked_up.contents);
+}
+
+mod prefixed {
+    wheatley::embed_assets! {
+        location: "examples/books",
+        prefix: "/books/",
+        constants: assets,
+    }
+
+    #[test]
+    fn leave_prefix_out_of_modules() {
+        let file = assets::suzanne_lang::GRUMPY_MONKEY_TXT.file();
+
+        assert_eq!(file.path, b"/books/suzanne_lang/grumpy_monkey.txt");
+    }
+}
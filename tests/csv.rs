@@ -0,0 +1,36 @@
+#![cfg(feature = "csv")]
+
+#[derive(wheatley::Wheatley, Debug, PartialEq)]
+#[wheatley(csv = "examples/books.csv", key = "title")]
+enum Books {
+    TheCatInTheHat,
+    ScrambledEggsSuper,
+    WackyWednesday,
+    InAPeopleHouse,
+    ThePoutPoutFish,
+    GrumpyMonkey,
+    HardlyHaunted,
+}
+
+#[test]
+fn parse_variant_from_key_column() {
+    assert_eq!(Books::try_from("Scrambled Eggs Super!"), Ok(Books::ScrambledEggsSuper));
+    assert!(Books::try_from("Green Eggs and Ham").is_err());
+}
+
+#[test]
+fn read_typed_columns() {
+    assert_eq!(Books::WackyWednesday.published(), 1974);
+    assert_eq!(Books::GrumpyMonkey.author(), "Suzanne Lang");
+    assert_eq!(Books::InAPeopleHouse.pen_name(), Some("Theo LeSieg"));
+    assert_eq!(Books::HardlyHaunted.pen_name(), None);
+}
+
+#[test]
+fn read_row() {
+    assert_eq!(
+        Books::ThePoutPoutFish.contents(),
+        "The Pout-Pout Fish,Deborah Diesen,2008,"
+    );
+    assert_eq!(Books::TheCatInTheHat.path(), "books.csv:2");
+}
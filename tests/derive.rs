@@ -0,0 +1,157 @@
+use std::str::FromStr;
+use wheatley::ParseAssetError;
+
+#[derive(wheatley::Wheatley, Debug, PartialEq)]
+#[wheatley(location = "examples/cars")]
+enum Cars {
+    Civic,
+    Forte,
+    ModelY,
+}
+
+#[test]
+fn parse_variant_from_file_stem() {
+    assert_eq!(Cars::try_from("civic"), Ok(Cars::Civic));
+    assert_eq!(Cars::from_str("model_y"), Ok(Cars::ModelY));
+    assert_eq!("forte".parse::<Cars>(), Ok(Cars::Forte));
+}
+
+#[test]
+fn reject_unknown_name() {
+    assert_eq!(Cars::try_from("corolla"), Err(ParseAssetError::new("Cars")));
+    assert_eq!(Cars::try_from("Civic"), Err(ParseAssetError::new("Cars")));
+}
+
+#[test]
+fn read_variant_file() {
+    let expected_contents = std::fs::read_to_string("examples/cars/model_y.toml").unwrap();
+
+    assert_eq!(Cars::ModelY.contents(), expected_contents);
+    assert_eq!(Cars::ModelY.path(), "model_y.toml");
+}
+
+#[test]
+fn read_variant_properties() {
+    assert_eq!(Cars::Civic.make(), "Honda");
+    assert_eq!(Cars::ModelY.body_style(), "crossover");
+}
+
+#[derive(wheatley::Wheatley, Debug, PartialEq)]
+#[wheatley(location = "examples/cars", allow_extra_files)]
+enum Hondas {
+    Civic,
+}
+
+#[test]
+fn allow_files_without_variant() {
+    assert_eq!(Hondas::Civic.make(), "Honda");
+    assert!(Hondas::try_from("forte").is_err());
+}
+
+#[test]
+fn keys_missing_from_some_files_are_optional() {
+    assert_eq!(Cars::Civic.trim(), Some("Si"));
+    assert_eq!(Cars::Forte.trim(), None);
+}
+
+#[derive(wheatley::Wheatley, Debug, PartialEq)]
+#[wheatley(location = "examples/cars", schema = "examples/cars_schema.toml")]
+enum DeclaredCars {
+    Civic,
+    Forte,
+    ModelY,
+}
+
+#[test]
+fn read_properties_declared_by_schema() {
+    assert_eq!(DeclaredCars::Forte.make(), "Kia");
+    assert_eq!(DeclaredCars::Civic.trim(), Some("Si"));
+    assert_eq!(DeclaredCars::ModelY.colour(), None);
+}
+
+#[test]
+fn read_typed_properties() {
+    assert_eq!(Cars::Forte.year(), 2023);
+    assert_eq!(Cars::Civic.mpg(), 36.5);
+    assert!(Cars::ModelY.electric());
+    assert_eq!(Cars::Civic.colours(), ["red", "blue"]);
+    assert!(Cars::Forte.colours().is_empty());
+}
+
+#[test]
+fn read_optional_datetime() {
+    let released = Cars::Civic.released().unwrap();
+
+    assert_eq!(
+        released.date,
+        Some(wheatley::Date {
+            year: 1972,
+            month: 7,
+            day: 11
+        })
+    );
+    assert_eq!(released.to_string(), "1972-07-11");
+    assert_eq!(Cars::ModelY.released(), None);
+}
+
+#[test]
+fn read_nested_tables() {
+    assert_eq!(
+        Cars::Civic.engine(),
+        &CarsEngine {
+            cylinders: 4,
+            turbo: Some(true)
+        }
+    );
+    assert_eq!(Cars::Forte.engine().turbo, None);
+    assert_eq!(DeclaredCars::ModelY.engine().cylinders, 0);
+}
+
+#[test]
+fn read_arrays_of_tables() {
+    let trims = Cars::Civic.trims();
+
+    assert_eq!(trims.len(), 2);
+    assert_eq!(trims[1], CarsTrims { name: "Si", price: 29950 });
+    assert!(Cars::ModelY.trims().is_empty());
+    assert_eq!(DeclaredCars::Forte.trims()[0].name, "LXS");
+}
+
+#[test]
+fn list_every_variant() {
+    assert_eq!(Cars::ALL, [Cars::Civic, Cars::Forte, Cars::ModelY]);
+    assert_eq!(Cars::COUNT, 3);
+    assert_eq!(Cars::iter().map(Cars::path).collect::<Vec<_>>(), ["civic.toml", "forte.toml", "model_y.toml"]);
+}
+
+#[test]
+fn display_file_stem() {
+    assert_eq!(Cars::ModelY.to_string(), "model_y");
+    assert_eq!(Cars::ModelY.name(), "model_y");
+    assert_eq!(Cars::ModelY.to_string().parse::<Cars>(), Ok(Cars::ModelY));
+}
+
+#[derive(wheatley::Wheatley, Debug, PartialEq)]
+#[wheatley(location = "examples/languages")]
+enum Languages {
+    #[wheatley(file = "c++.toml", alias = "cpp", alias = "c-plus-plus")]
+    Cpp,
+    #[wheatley(alias = "rs")]
+    Rust,
+}
+
+#[test]
+fn back_variant_with_named_file() {
+    assert_eq!(Languages::Cpp.path(), "c++.toml");
+    assert_eq!(Languages::Cpp.name(), "c++");
+    assert_eq!(Languages::Cpp.designer(), "Bjarne Stroustrup");
+}
+
+#[test]
+fn parse_variant_from_alias() {
+    assert_eq!(Languages::try_from("c++"), Ok(Languages::Cpp));
+    assert_eq!(Languages::try_from("cpp"), Ok(Languages::Cpp));
+    assert_eq!("c-plus-plus".parse::<Languages>(), Ok(Languages::Cpp));
+    assert_eq!(Languages::try_from("rs"), Ok(Languages::Rust));
+    assert_eq!(Languages::try_from("rust"), Ok(Languages::Rust));
+}
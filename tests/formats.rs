@@ -0,0 +1,29 @@
+#![cfg(all(feature = "json", feature = "yaml", feature = "ron"))]
+
+#[derive(wheatley::Wheatley, Debug, PartialEq)]
+#[wheatley(location = "examples/trucks")]
+enum Trucks {
+    F150,
+    Tacoma,
+    Cybertruck,
+}
+
+#[test]
+fn read_each_format_by_extension() {
+    assert_eq!(Trucks::F150.make(), "Ford");
+    assert_eq!(Trucks::Tacoma.towing_lbs(), 6500);
+    assert_eq!(Trucks::Cybertruck.engine(), &TrucksEngine { cylinders: 0 });
+    assert_eq!(Trucks::Tacoma.path(), "tacoma.yaml");
+}
+
+#[derive(wheatley::Wheatley, Debug, PartialEq)]
+#[wheatley(location = "examples/boats", format = "json")]
+enum Boats {
+    Sunfish,
+}
+
+#[test]
+fn read_format_given_by_attribute() {
+    assert_eq!(Boats::Sunfish.builder(), "Alcort");
+    assert_eq!(Boats::Sunfish.length_ft(), 13.9);
+}
@@ -0,0 +1,36 @@
+use wheatley::{embed_assets, Encoding, Silo, Wheatley};
+
+fn read_contents<S: Silo>(silo: &S, key: &S::Key) -> Option<String> {
+    silo.get(key)
+        .map(|file| String::from_utf8(file.contents.to_vec()).unwrap())
+}
+
+#[test]
+fn read_assets_by_integer_key() {
+    let sprites: Wheatley<u32> = embed_assets! {
+        location: "examples/sprites",
+        key: u32 from "sprite_{id}.txt",
+    };
+
+    let sprite = sprites.get(42).unwrap();
+
+    assert_eq!(sprite.path, b"sprite_42.txt");
+    assert!(sprites.get(7).is_none());
+
+    // Codec features embed the contents compressed
+    if sprites.encoding() == Encoding::Identity {
+        assert_eq!(sprite.contents, b"sprite number 42\n");
+        assert_eq!(read_contents(&sprites, &3).unwrap(), "sprite number 3\n");
+    }
+}
+
+#[test]
+fn match_key_pattern_after_prefix() {
+    let sprites: Wheatley<u32> = embed_assets! {
+        location: "examples/sprites",
+        prefix: "/sprites/",
+        key: u32 from "sprite_{id}.txt",
+    };
+
+    assert_eq!(sprites.get(42).unwrap().path, b"/sprites/sprite_42.txt");
+}
@@ -0,0 +1,33 @@
+#[derive(wheatley::Wheatley, Debug, Clone, Copy, PartialEq)]
+#[wheatley(location = "examples/errors", default_locale = "en")]
+enum Errors {
+    NotFound,
+    PermissionDenied,
+}
+
+#[test]
+fn list_locales() {
+    assert_eq!(ErrorsLocale::ALL, [ErrorsLocale::En, ErrorsLocale::Fr, ErrorsLocale::FrCa]);
+    assert_eq!(ErrorsLocale::default(), ErrorsLocale::En);
+    assert_eq!(ErrorsLocale::FrCa.code(), "fr-CA");
+    assert_eq!("fr-CA".parse::<ErrorsLocale>(), Ok(ErrorsLocale::FrCa));
+}
+
+#[test]
+fn read_property_in_locale() {
+    assert_eq!(Errors::NotFound.message(ErrorsLocale::En), "The asset could not be found");
+    assert_eq!(Errors::NotFound.message(ErrorsLocale::Fr), "L'élément est introuvable");
+    assert_eq!(Errors::PermissionDenied.status(ErrorsLocale::FrCa), 403);
+    assert_eq!(Errors::PermissionDenied.path(ErrorsLocale::FrCa), "fr-CA/permission_denied.toml");
+}
+
+#[test]
+fn fall_back_to_shorter_then_default_locale() {
+    assert_eq!(ErrorsLocale::FrCa.fallback(), Some(ErrorsLocale::Fr));
+    assert_eq!(ErrorsLocale::Fr.fallback(), Some(ErrorsLocale::En));
+    assert_eq!(ErrorsLocale::En.fallback(), None);
+
+    assert_eq!(Errors::NotFound.message(ErrorsLocale::FrCa), "L'élément est introuvable");
+    assert_eq!(Errors::PermissionDenied.message(ErrorsLocale::Fr), "You may not read this asset");
+    assert_eq!(Errors::PermissionDenied.path(ErrorsLocale::Fr), "en/permission_denied.toml");
+}
@@ -0,0 +1,61 @@
+#[derive(wheatley::Wheatley, Debug, PartialEq)]
+#[wheatley(location = "examples/posts")]
+enum Posts {
+    HelloWorld,
+    ReleaseNotes,
+}
+
+#[test]
+fn read_front_matter_properties() {
+    assert_eq!(Posts::HelloWorld.title(), "Hello, world");
+    assert_eq!(Posts::ReleaseNotes.tags(), ["release", "compression"]);
+    assert_eq!(Posts::ReleaseNotes.published().to_string(), "2025-01-15");
+    assert_eq!(Posts::HelloWorld.draft(), None);
+}
+
+#[test]
+fn split_body_from_front_matter() {
+    let expected_contents = std::fs::read_to_string("examples/posts/hello_world.md").unwrap();
+
+    assert_eq!(Posts::HelloWorld.contents(), expected_contents);
+    assert!(Posts::HelloWorld.body().starts_with("# Hello, world\n"));
+}
+
+#[cfg(feature = "markdown")]
+#[test]
+fn render_body_to_html() {
+    assert_eq!(
+        Posts::ReleaseNotes.html(),
+        "<h1>Release notes</h1>\n<p>Silos can now be compressed with <em>zstd</em>.</p>\n"
+    );
+}
+
+#[test]
+fn embed_markdown_bodies() {
+    let silo = wheatley::embed_assets! {
+        location: "examples/posts",
+        markdown: "body",
+    };
+
+    let post = silo.get("hello_world.md").unwrap();
+
+    // Codec features embed the contents compressed
+    if silo.encoding() == wheatley::Encoding::Identity {
+        assert!(post.contents.starts_with(b"# Hello, world\n"));
+    }
+}
+
+#[cfg(feature = "markdown")]
+#[test]
+fn embed_markdown_as_html() {
+    let silo = wheatley::embed_assets! {
+        location: "examples/posts",
+        markdown: "html",
+    };
+
+    let post = silo.get("release_notes.md").unwrap();
+
+    if silo.encoding() == wheatley::Encoding::Identity {
+        assert!(post.contents.starts_with(b"<h1>Release notes</h1>"));
+    }
+}
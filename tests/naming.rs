@@ -0,0 +1,29 @@
+mod garage {
+    #[derive(wheatley::Wheatley, Debug, PartialEq)]
+    #[wheatley(location = "examples/models", vis = "pub(crate)", rename(top_speed = "max_speed"))]
+    pub enum Models {
+        Roadster,
+        Hatch,
+    }
+}
+
+use garage::Models;
+
+const ROADSTER_TYPE: &str = Models::Roadster.r#type();
+
+#[test]
+fn escape_property_names() {
+    assert_eq!(ROADSTER_TYPE, "electric");
+    assert_eq!(Models::Hatch.body_style(), "hatchback");
+}
+
+#[test]
+fn rename_property_methods() {
+    assert_eq!(Models::Roadster.max_speed(), 250);
+}
+
+#[test]
+fn call_generated_methods_from_outside_the_module() {
+    assert_eq!(Models::COUNT, 2);
+    assert_eq!(Models::Hatch.path(), "hatch.toml");
+}
@@ -0,0 +1,75 @@
+#[derive(wheatley::Wheatley)]
+#[derive(Debug, PartialEq, PartialOrd)]
+#[wheatley(location = "examples/books", raw, allow_extra_files)]
+enum Books {
+    HardlyHaunted,
+    DeborahDiesen(books::DeborahDiesen),
+    TheodorSeussGeisel(books::TheodorSeussGeisel),
+}
+
+use books::theodor_seuss_geisel::DrSeuss;
+use books::TheodorSeussGeisel;
+
+#[test]
+fn read_file_of_nested_directory() {
+    let book = Books::TheodorSeussGeisel(TheodorSeussGeisel::DrSeuss(DrSeuss::CatInTheHat));
+    let expected_contents =
+        std::fs::read_to_string("examples/books/theodor_seuss_geisel/dr_seuss/cat_in_the_hat.txt").unwrap();
+
+    assert_eq!(book.path(), "theodor_seuss_geisel/dr_seuss/cat_in_the_hat.txt");
+    assert_eq!(book.as_str(), Some(expected_contents.as_str()));
+}
+
+#[test]
+fn parse_names_relative_to_location() {
+    let book = Books::DeborahDiesen(books::DeborahDiesen::PoutPoutFish);
+
+    assert_eq!(book.name(), "deborah_diesen/pout_pout_fish");
+    assert_eq!(Books::try_from("deborah_diesen/pout_pout_fish"), Ok(book));
+    assert_eq!(Books::try_from("hardly_haunted"), Ok(Books::HardlyHaunted));
+    assert!(Books::try_from("pout_pout_fish").is_err());
+}
+
+#[test]
+fn list_every_file_of_the_tree() {
+    assert_eq!(Books::COUNT, 6);
+    assert_eq!(Books::ALL[0], Books::HardlyHaunted);
+    assert_eq!(Books::ALL[1], Books::DeborahDiesen(books::DeborahDiesen::PoutPoutFish));
+    assert_eq!(
+        Books::iter().filter(|book| matches!(book, Books::TheodorSeussGeisel(_))).count(),
+        4
+    );
+}
+
+#[test]
+fn match_nested_enums_exhaustively() {
+    let author = |book: &Books| match book {
+        Books::HardlyHaunted => "Jessie Sima",
+        Books::DeborahDiesen(_) => "Deborah Diesen",
+        Books::TheodorSeussGeisel(TheodorSeussGeisel::DrSeuss(_)) => "Dr. Seuss",
+        Books::TheodorSeussGeisel(TheodorSeussGeisel::TheoLesieg(_)) => "Theo LeSieg",
+    };
+
+    assert_eq!(author(&Books::ALL[2]), "Dr. Seuss");
+}
+
+#[test]
+fn derive_what_the_parent_derives() {
+    assert!(DrSeuss::ALL[0] < DrSeuss::ALL[1]);
+    assert_eq!(format!("{:?}", DrSeuss::ALL[0]), "CatInTheHat");
+}
+
+#[derive(wheatley::Wheatley)]
+#[wheatley(location = "examples/menu")]
+enum Menu {
+    Coffee,
+    Teas(menu::Teas),
+}
+
+#[test]
+fn share_properties_with_nested_enums() {
+    assert_eq!(Menu::Coffee.title(), "Filter coffee");
+    assert_eq!(Menu::Coffee.origin(), None);
+    assert_eq!(Menu::Teas(menu::Teas::Green).origin(), Some("Shizuoka"));
+    assert_eq!(menu::Teas::Black.price(), 2.8);
+}
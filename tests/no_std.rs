@@ -0,0 +1,37 @@
+use std::path::Path;
+use std::process::Command;
+
+const EMBEDDED_TARGET: &str = "thumbv7em-none-eabihf";
+
+fn target_installed(target: &str) -> bool {
+    Command::new("rustup")
+        .args(["target", "list", "--installed"])
+        .output()
+        .map(|output| String::from_utf8_lossy(&output.stdout).lines().any(|line| line == target))
+        .unwrap_or(false)
+}
+
+// Builds a `#![no_std]` crate embedding assets. Without an embedded target
+// installed it is built for the host, which still rules out std and alloc.
+#[test]
+fn build_without_std() {
+    let manifest = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/no_std/Cargo.toml");
+
+    let mut build = Command::new(env!("CARGO"));
+    build
+        .arg("build")
+        .arg("--manifest-path")
+        .arg(manifest)
+        .arg("--target-dir")
+        .arg(Path::new(env!("CARGO_TARGET_TMPDIR")).join("no_std"));
+
+    if target_installed(EMBEDDED_TARGET) {
+        build.args(["--target", EMBEDDED_TARGET]);
+    } else {
+        eprintln!("{EMBEDDED_TARGET} is not installed, building for the host instead");
+    }
+
+    let status = build.status().unwrap();
+
+    assert!(status.success());
+}
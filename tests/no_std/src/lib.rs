@@ -0,0 +1,11 @@
+#![no_std]
+
+use wheatley::Wheatley;
+
+static BOOKS: Wheatley = wheatley::embed_assets! {
+    location: "$CARGO_MANIFEST_DIR/../../examples/books",
+};
+
+pub fn find(path: &str) -> Option<&'static [u8]> {
+    BOOKS.get(path).map(|file| file.contents)
+}
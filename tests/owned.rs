@@ -0,0 +1,67 @@
+#![cfg(feature = "owned")]
+
+use std::path::{Path, PathBuf};
+use wheatley::{embed_assets, Options, OwnedWheatley, Silo};
+
+fn read_fixture<T: AsRef<Path>>(filename: T) -> String {
+    let mut file_path = PathBuf::from("./examples/books");
+    file_path.push(filename);
+
+    let file_path = file_path.canonicalize().unwrap();
+    std::fs::read_to_string(file_path).unwrap()
+}
+
+fn read_contents<S: Silo<Key = str>>(silo: &S, key: &str) -> Option<String> {
+    silo.get(key)
+        .map(|file| String::from_utf8(file.contents.to_vec()).unwrap())
+}
+
+#[test]
+fn read_assets_from_dir_at_runtime() {
+    let expected_contents = read_fixture("theodor_seuss_geisel/dr_seuss/cat_in_the_hat.txt");
+
+    let silo = OwnedWheatley::from_dir("examples/books", &Options::default()).unwrap();
+
+    assert_eq!(
+        read_contents(&silo, "theodor_seuss_geisel/dr_seuss/cat_in_the_hat.txt").unwrap(),
+        expected_contents
+    );
+    assert!(silo.get("green_eggs_and_ham.txt").is_none());
+}
+
+#[test]
+fn owned_and_embedded_silos_are_interchangeable() {
+    let embedded: wheatley::Wheatley = embed_assets! {
+        location: "examples/books",
+        prefix: "/books/",
+    };
+    let owned = OwnedWheatley::from_dir(
+        "examples/books",
+        &Options {
+            prefix: String::from("/books/"),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    assert_eq!(embedded.len(), owned.len());
+
+    for file in embedded.iter() {
+        let key = std::str::from_utf8(file.path).unwrap();
+
+        assert_eq!(read_contents(&embedded, key), read_contents(&owned, key));
+    }
+}
+
+#[test]
+fn owned_silo_round_trips_through_bundle() {
+    let silo = OwnedWheatley::from_dir("examples/books", &Options::default()).unwrap();
+    let reloaded = OwnedWheatley::from_bundle(silo.as_bytes()).unwrap();
+
+    assert_eq!(silo.len(), reloaded.len());
+    for file in silo.iter() {
+        let key = std::str::from_utf8(file.path).unwrap();
+
+        assert_eq!(read_contents(&silo, key), read_contents(&reloaded, key));
+    }
+}
@@ -0,0 +1,47 @@
+#[derive(wheatley::Wheatley, Debug, PartialEq)]
+#[wheatley(location = "examples/books", raw)]
+enum Books {
+    HardlyHaunted,
+    HugACactus,
+}
+
+#[test]
+fn embed_text_files_as_they_are() {
+    let expected_contents = std::fs::read_to_string("examples/books/hug_a_cactus.txt").unwrap();
+
+    assert_eq!(Books::HugACactus.as_str(), Some(expected_contents.as_str()));
+    assert_eq!(Books::HugACactus.bytes(), expected_contents.as_bytes());
+    assert_eq!(Books::HardlyHaunted.path(), "hardly_haunted.txt");
+}
+
+#[derive(wheatley::Wheatley, Debug, PartialEq)]
+#[wheatley(location = "examples/posts", raw)]
+enum Posts {
+    HelloWorld,
+    ReleaseNotes,
+}
+
+#[test]
+fn keep_front_matter_of_raw_markdown() {
+    assert!(Posts::HelloWorld.as_str().unwrap().starts_with("+++\ntitle"));
+}
+
+#[derive(wheatley::Wheatley, Debug, PartialEq)]
+#[wheatley(location = "examples/glyphs", raw)]
+enum Glyphs {
+    Arrow,
+    Check,
+}
+
+#[test]
+fn embed_binary_files_as_bytes() {
+    assert_eq!(Glyphs::Arrow.bytes(), [0x89, 0xff, 0x00, 0x10, 0xfe]);
+    assert_eq!(Glyphs::Check.bytes(), "→\n".as_bytes());
+    assert_eq!(Glyphs::Check.path(), "check.txt");
+}
+
+#[test]
+fn read_only_utf8_files_as_str() {
+    assert_eq!(Glyphs::Arrow.as_str(), None);
+    assert_eq!(Glyphs::Check.as_str(), Some("→\n"));
+}
@@ -0,0 +1,62 @@
+#![cfg(feature = "serde")]
+
+#[derive(wheatley::Wheatley, Debug, PartialEq)]
+#[wheatley(location = "examples/cars")]
+enum Cars {
+    Civic,
+    Forte,
+    ModelY,
+}
+
+#[test]
+fn serialize_file_stem() {
+    assert_eq!(serde_json::to_string(&Cars::ModelY).unwrap(), r#""model_y""#);
+    assert_eq!(serde_json::to_string(&[Cars::Civic, Cars::Forte]).unwrap(), r#"["civic","forte"]"#);
+}
+
+#[test]
+fn deserialize_file_stem() {
+    assert_eq!(serde_json::from_str::<Cars>(r#""model_y""#).unwrap(), Cars::ModelY);
+
+    let error = serde_json::from_str::<Cars>(r#""corolla""#).unwrap_err();
+    assert!(error.to_string().contains("unknown variant `corolla`"));
+}
+
+#[derive(wheatley::Wheatley, Debug, PartialEq)]
+#[wheatley(location = "examples/cars", allow_extra_files)]
+enum Garage<const SPACES: usize = 2> {
+    Civic,
+    Forte,
+}
+
+#[test]
+fn round_trip_generic_enum() {
+    assert_eq!(serde_json::to_string(&Garage::<2>::Forte).unwrap(), r#""forte""#);
+    assert_eq!(serde_json::from_str::<Garage<3>>(r#""civic""#).unwrap(), Garage::Civic);
+}
+
+#[derive(wheatley::Wheatley)]
+#[derive(Debug, PartialEq)]
+#[wheatley(location = "examples/books", raw, allow_extra_files)]
+enum Books {
+    #[wheatley(alias = "haunted")]
+    HardlyHaunted,
+    DeborahDiesen(books::DeborahDiesen),
+}
+
+#[test]
+fn round_trip_nested_variant() {
+    let book = Books::DeborahDiesen(books::DeborahDiesen::PoutPoutFish);
+
+    assert_eq!(serde_json::to_string(&book).unwrap(), r#""deborah_diesen/pout_pout_fish""#);
+    assert_eq!(serde_json::from_str::<Books>(r#""deborah_diesen/pout_pout_fish""#).unwrap(), book);
+}
+
+#[test]
+fn expect_aliases_and_nested_names() {
+    let error = serde_json::from_str::<Books>(r#""grumpy_monkey""#).unwrap_err();
+
+    assert!(error
+        .to_string()
+        .contains("expected one of `hardly_haunted`, `haunted`, `deborah_diesen/pout_pout_fish`"));
+}
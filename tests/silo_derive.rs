@@ -0,0 +1,47 @@
+use wheatley::{Silo, WheatleySilo};
+
+#[derive(WheatleySilo)]
+#[wheatley(location = "examples/books")]
+struct Books;
+
+#[derive(WheatleySilo)]
+#[wheatley(location = "examples/books", prefix = "/books/")]
+struct PrefixedBooks;
+
+fn count<S: Silo>(silo: &S) -> usize {
+    silo.iter().count()
+}
+
+#[test]
+fn get_file_through_associated_function() {
+    let file = Books::get("hardly_haunted.txt").unwrap();
+
+    assert_eq!(file.path, b"hardly_haunted.txt");
+    assert!(Books::get("missing.txt").is_none());
+
+    // Codec features embed the contents compressed
+    if Books::silo().encoding() == wheatley::Encoding::Identity {
+        let expected_contents = std::fs::read_to_string("examples/books/hardly_haunted.txt").unwrap();
+
+        assert_eq!(file.contents, expected_contents.as_bytes());
+    }
+}
+
+#[test]
+fn iterate_over_every_file() {
+    assert_eq!(Books::iter().count(), Books::len());
+    assert_eq!(Books::len(), Books::silo().len());
+}
+
+#[test]
+fn apply_embed_assets_options() {
+    assert!(PrefixedBooks::get("/books/hardly_haunted.txt").is_some());
+    assert!(PrefixedBooks::get("hardly_haunted.txt").is_none());
+}
+
+#[test]
+fn pass_derived_silo_as_generic_silo() {
+    assert_eq!(count(&Books), Books::len());
+    assert_eq!(Silo::get(&Books, "hardly_haunted.txt").unwrap().path, b"hardly_haunted.txt");
+    assert!(!Books.is_empty());
+}
@@ -0,0 +1,35 @@
+#[derive(Debug, PartialEq)]
+struct Database {
+    host: &'static str,
+    port: u16,
+}
+
+#[derive(wheatley::Wheatley, Debug, PartialEq)]
+#[wheatley(file = "examples/config/app.toml")]
+struct AppConfig {
+    port: u16,
+    name: &'static str,
+    features: &'static [&'static str],
+    ratio: f32,
+    released: wheatley::Datetime,
+    timeout: Option<u32>,
+    database: Database,
+}
+
+#[test]
+fn embed_file_into_constant() {
+    assert_eq!(APP_CONFIG.port, 8080);
+    assert_eq!(APP_CONFIG.name, "wheatley");
+    assert_eq!(APP_CONFIG.features, ["gzip", "zstd"]);
+    assert_eq!(APP_CONFIG.ratio, 0.75);
+    assert_eq!(APP_CONFIG.released.to_string(), "2024-11-02");
+    assert_eq!(APP_CONFIG.timeout, None);
+    assert_eq!(APP_CONFIG.database, Database { host: "localhost", port: 5432 });
+}
+
+#[test]
+fn load_static_reference() {
+    let config: &'static AppConfig = AppConfig::load();
+
+    assert_eq!(config, &APP_CONFIG);
+}
@@ -0,0 +1,23 @@
+#![cfg(feature = "alloc")]
+
+#[derive(wheatley::Wheatley, Debug, PartialEq)]
+#[wheatley(location = "examples/greetings", template = "greeting", template = "farewell")]
+enum Greetings {
+    Welcome,
+    WelcomeBack,
+}
+
+#[test]
+fn fill_template_placeholders() {
+    assert_eq!(Greetings::Welcome.greeting("Sam", 3), "Hello Sam, you have 3 items");
+    assert_eq!(
+        Greetings::WelcomeBack.greeting("Sam", 3),
+        "Welcome back Sam! 3 items are waiting, {still} Sam"
+    );
+}
+
+#[test]
+fn optional_templates_give_none_where_missing() {
+    assert_eq!(Greetings::Welcome.farewell("Sam").as_deref(), Some("Goodbye Sam"));
+    assert_eq!(Greetings::WelcomeBack.farewell("Sam"), None);
+}
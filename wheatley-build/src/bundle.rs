@@ -0,0 +1,119 @@
+// Writer for `.wheat` bundles. The layout must stay in sync with the
+// reader in `wheatley::bundle`.
+
+use crate::Layout;
+use std::io::{self, Write};
+
+pub const MAGIC: &[u8; 8] = b"WHEATLEY";
+pub const VERSION: u16 = 1;
+
+const SECTION_TABLE: usize = 16;
+const SECTION_COUNT: usize = 8;
+const HEADER_LEN: usize = SECTION_TABLE + SECTION_COUNT * 16;
+
+/// How the contents of every file in a silo are encoded.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Encoding {
+    #[default]
+    Identity,
+    Gzip,
+    Brotli,
+    Snappy,
+    Zstd,
+}
+
+impl Encoding {
+    pub fn tag(self) -> u8 {
+        match self {
+            Encoding::Identity => 0,
+            Encoding::Gzip => 1,
+            Encoding::Brotli => 2,
+            Encoding::Snappy => 3,
+            Encoding::Zstd => 4,
+        }
+    }
+}
+
+fn u64s_to_le(values: &[u64]) -> Vec<u8> {
+    values.iter().flat_map(|value| value.to_le_bytes()).collect()
+}
+
+fn u32s_to_le(values: &[u32]) -> Vec<u8> {
+    values.iter().flat_map(|value| value.to_le_bytes()).collect()
+}
+
+impl Layout {
+    /// The tables of the layout as little-endian bytes, in bundle order:
+    /// level capacities, words, ranks, fingerprints, key offsets, keys,
+    /// content offsets and contents.
+    pub fn sections(&self) -> [Vec<u8>; SECTION_COUNT] {
+        [
+            u64s_to_le(&self.level_bits),
+            u64s_to_le(&self.words),
+            u64s_to_le(&self.ranks),
+            u64s_to_le(&self.fingerprints),
+            u32s_to_le(&self.key_offsets),
+            self.keys.clone(),
+            u32s_to_le(&self.content_offsets),
+            self.contents.clone(),
+        ]
+    }
+
+    /// Serialise the layout as a `.wheat` bundle, e.g. from a build script.
+    pub fn write_bundle<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        let sections = self.sections();
+
+        let mut header = Vec::with_capacity(HEADER_LEN);
+        header.extend_from_slice(MAGIC);
+        header.extend_from_slice(&VERSION.to_le_bytes());
+        header.push(self.encoding.tag());
+        header.resize(SECTION_TABLE, 0);
+
+        let mut offset = HEADER_LEN as u64;
+        for section in sections.iter() {
+            let length = section.len() as u64;
+            header.extend_from_slice(&offset.to_le_bytes());
+            header.extend_from_slice(&length.to_le_bytes());
+            offset += length;
+        }
+
+        writer.write_all(&header)?;
+        for section in sections.iter() {
+            writer.write_all(section)?;
+        }
+
+        writer.flush()
+    }
+
+    pub fn to_bundle(&self) -> Vec<u8> {
+        let mut bundle = Vec::new();
+        self.write_bundle(&mut bundle)
+            .expect("Writing to a Vec cannot fail");
+
+        bundle
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sections_follow_the_header() {
+        let layout = Layout::from_assets(vec![
+            (String::from("foo"), b"bar".to_vec()),
+            (String::from("zoo"), b"books".to_vec()),
+        ]);
+
+        let bundle = layout.to_bundle();
+        let sections = layout.sections();
+
+        assert_eq!(&bundle[..8], MAGIC);
+        assert_eq!(bundle[10], Encoding::Identity.tag());
+        assert_eq!(
+            bundle.len(),
+            HEADER_LEN + sections.iter().map(Vec::len).sum::<usize>()
+        );
+        assert!(bundle.ends_with(&layout.contents));
+    }
+}
@@ -0,0 +1,69 @@
+/// A file name pattern with a single `{name}` placeholder, such as
+/// `sprite_{id}.png`, used to derive typed keys from paths.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct KeyPattern {
+    prefix: String,
+    placeholder: String,
+    suffix: String,
+}
+
+impl KeyPattern {
+    pub fn parse(pattern: &str) -> Result<Self, String> {
+        let (prefix, rest) = pattern
+            .split_once('{')
+            .ok_or_else(|| format!("Key pattern `{pattern}` has no `{{...}}` placeholder"))?;
+        let (placeholder, suffix) = rest
+            .split_once('}')
+            .ok_or_else(|| format!("Key pattern `{pattern}` has an unclosed placeholder"))?;
+
+        if placeholder.is_empty() {
+            return Err(format!("Key pattern `{pattern}` has an unnamed placeholder"));
+        }
+
+        if suffix.contains(['{', '}']) {
+            return Err(format!("Key pattern `{pattern}` may only hold one placeholder"));
+        }
+
+        Ok(Self {
+            prefix: prefix.to_owned(),
+            placeholder: placeholder.to_owned(),
+            suffix: suffix.to_owned(),
+        })
+    }
+
+    pub fn placeholder(&self) -> &str {
+        &self.placeholder
+    }
+
+    /// The part of `path` standing in for the placeholder, or `None` when
+    /// `path` does not have the pattern's shape.
+    pub fn capture<'a>(&self, path: &'a str) -> Option<&'a str> {
+        path.strip_prefix(&self.prefix)?
+            .strip_suffix(&self.suffix)
+            .filter(|capture| !capture.is_empty())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    #[test_case("sprite_12.png", Some("12"); "matching name")]
+    #[test_case("sprite_.png", None; "empty capture")]
+    #[test_case("sprite_12.jpg", None; "other extension")]
+    #[test_case("tiles/sprite_12.png", None; "other directory")]
+    fn capture_placeholder(path: &str, expected: Option<&str>) {
+        let pattern = KeyPattern::parse("sprite_{id}.png").unwrap();
+
+        assert_eq!(pattern.capture(path), expected);
+    }
+
+    #[test_case("sprite.png"; "no placeholder")]
+    #[test_case("sprite_{id.png"; "unclosed placeholder")]
+    #[test_case("sprite_{}.png"; "unnamed placeholder")]
+    #[test_case("{x}_{y}.png"; "two placeholders")]
+    fn reject_malformed_pattern(pattern: &str) {
+        assert!(KeyPattern::parse(pattern).is_err());
+    }
+}
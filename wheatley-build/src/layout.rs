@@ -0,0 +1,142 @@
+use crate::mphf::bbhash::{fingerprint, Mphf};
+use crate::{build_hash_table, Encoding};
+use std::fmt::Debug;
+use std::hash::Hash;
+
+/// The flat tables a `wheatley::Wheatley` is built from.
+///
+/// Paths are concatenated into `keys` and assets into `contents`. Entry `i`
+/// spans `key_offsets[i]..key_offsets[i + 1]` and
+/// `content_offsets[i]..content_offsets[i + 1]`. The hasher levels are
+/// flattened the same way so the whole silo costs a handful of relocations.
+#[derive(Debug, Default, PartialEq)]
+pub struct Layout {
+    pub encoding: Encoding,
+    pub level_bits: Vec<u64>,
+    pub words: Vec<u64>,
+    pub ranks: Vec<u64>,
+    pub fingerprints: Vec<u64>,
+    pub keys: Vec<u8>,
+    pub key_offsets: Vec<u32>,
+    pub contents: Vec<u8>,
+    pub content_offsets: Vec<u32>,
+}
+
+impl Layout {
+    /// Hash `assets` by their keys and lay them out.
+    pub fn from_assets(assets: Vec<(String, Vec<u8>)>) -> Self {
+        let (hasher, hash_table) = build_hash_table(assets);
+
+        Self::new(&hasher, &hash_table)
+    }
+
+    /// Hash `assets` by a key other than their path, e.g. an id parsed from
+    /// the file name. Each entry is `(key, path, contents)`.
+    pub fn from_keyed_assets<K: Hash + Debug + Clone>(assets: Vec<(K, String, Vec<u8>)>) -> Self {
+        let assets = assets
+            .into_iter()
+            .map(|(key, path, contents)| (key, (path, contents)))
+            .collect();
+        let (hasher, hash_table) = build_hash_table(assets);
+
+        let mut layout = Self::with_hasher(&hasher);
+        for (key, (path, contents)) in &hash_table {
+            layout.push(key, path, contents);
+        }
+
+        layout
+    }
+
+    pub fn new(hasher: &Mphf<String>, hash_table: &[(String, Vec<u8>)]) -> Self {
+        let mut layout = Self::with_hasher(hasher);
+        for (key, contents) in hash_table {
+            layout.push(key, key, contents);
+        }
+
+        layout
+    }
+
+    /// The path of every entry, in the order of the entries.
+    pub fn paths(&self) -> impl Iterator<Item = &str> {
+        self.key_offsets.windows(2).map(|span| {
+            let path = &self.keys[span[0] as usize..span[1] as usize];
+
+            std::str::from_utf8(path).expect("Paths are pushed as strings")
+        })
+    }
+
+    fn with_hasher<K>(hasher: &Mphf<K>) -> Self {
+        let mut layout = Layout {
+            key_offsets: vec![0],
+            content_offsets: vec![0],
+            ..Default::default()
+        };
+
+        for (bit_vector, ranks) in hasher.bitvecs.iter() {
+            layout.level_bits.push(bit_vector.bits);
+            layout.words.extend_from_slice(&bit_vector.vector);
+            layout.ranks.extend_from_slice(ranks);
+        }
+
+        layout
+    }
+
+    fn push<K: Hash + ?Sized>(&mut self, key: &K, path: &str, contents: &[u8]) {
+        self.fingerprints.push(fingerprint(key));
+
+        self.keys.extend_from_slice(path.as_bytes());
+        self.key_offsets.push(to_offset(self.keys.len()));
+
+        self.contents.extend_from_slice(contents);
+        self.content_offsets.push(to_offset(self.contents.len()));
+    }
+}
+
+fn to_offset(length: usize) -> u32 {
+    u32::try_from(length).expect("Silos larger than 4 GiB are not supported")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn offsets_span_each_entry() {
+        let hash_table = vec![
+            (String::from("foo"), b"bar".to_vec()),
+            (String::from("quxx"), b"".to_vec()),
+            (String::from("z"), b"books".to_vec()),
+        ];
+        let keys = hash_table
+            .iter()
+            .map(|(key, _)| key.clone())
+            .collect::<Vec<_>>();
+        let hasher = Mphf::new(1.7, &keys);
+
+        let layout = Layout::new(&hasher, &hash_table);
+
+        assert_eq!(layout.keys, b"fooquxxz");
+        assert_eq!(layout.key_offsets, [0, 3, 7, 8]);
+        assert_eq!(layout.contents, b"barbooks");
+        assert_eq!(layout.content_offsets, [0, 3, 3, 8]);
+        assert_eq!(layout.fingerprints.len(), hash_table.len());
+        assert_eq!(layout.level_bits.len(), hasher.bitvecs.len());
+    }
+
+    #[test]
+    fn list_paths_in_entry_order() {
+        let hash_table = vec![
+            (String::from("css/main.css"), b"body {}".to_vec()),
+            (String::from("index.html"), b"<html>".to_vec()),
+        ];
+        let keys = hash_table
+            .iter()
+            .map(|(key, _)| key.clone())
+            .collect::<Vec<_>>();
+        let hasher = Mphf::new(1.7, &keys);
+
+        let layout = Layout::new(&hasher, &hash_table);
+
+        assert_eq!(layout.paths().collect::<Vec<_>>(), ["css/main.css", "index.html"]);
+    }
+}
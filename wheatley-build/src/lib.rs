@@ -0,0 +1,206 @@
+mod bundle;
+mod key_pattern;
+mod layout;
+pub mod mphf;
+
+pub use bundle::Encoding;
+pub use key_pattern::KeyPattern;
+pub use layout::Layout;
+
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::hash::Hash;
+use std::path::{Component, Path};
+use std::{fs, io};
+
+/// How the files of a directory are turned into keys.
+#[derive(Clone, Debug, Default)]
+pub struct Options {
+    pub prefix: String,
+    pub use_backslash_in_keys: bool,
+    pub ignore_globs: Vec<String>,
+}
+
+/// Read every file below `location` and key it by its path relative to
+/// `location`.
+pub fn gather<P: AsRef<Path>>(location: P, options: &Options) -> io::Result<HashMap<String, Vec<u8>>> {
+    let location = location.as_ref();
+    let Options {
+        prefix,
+        use_backslash_in_keys,
+        ..
+    } = options;
+
+    let mut asset_register = HashMap::new();
+    let mut dirs = std::collections::VecDeque::from([location.to_path_buf()]);
+
+    while let Some(dir) = dirs.pop_front() {
+        for entry in fs::read_dir(dir)? {
+            let entry = entry?;
+            let file_type = entry.file_type()?;
+
+            let path = entry.path();
+            if file_type.is_file() {
+                let relative_path = path.strip_prefix(location).unwrap();
+
+                let asset = fs::read(&path)?;
+
+                let separator = if cfg!(windows) && *use_backslash_in_keys {
+                    r"\"
+                } else {
+                    "/"
+                };
+
+                let asset_key = relative_path
+                    .components()
+                    .filter(|c| matches!(c, Component::Normal(_)))
+                    .map(|c| c.as_os_str().to_string_lossy().into_owned())
+                    .collect::<Vec<_>>()
+                    .join(separator);
+
+                let asset_key = prefix.clone() + &asset_key;
+
+                asset_register.insert(asset_key, asset);
+            } else if file_type.is_symlink() {
+                return Err(io::Error::new(
+                    io::ErrorKind::Unsupported,
+                    format!(
+                        "Encountered Symlink at: {}. Symlinks are not supported.",
+                        path.display()
+                    ),
+                ));
+            } else {
+                dirs.push_back(path);
+            }
+        }
+    }
+
+    Ok(asset_register)
+}
+
+/// Order `assets` so each one sits at the position the hasher gives its key.
+pub fn build_hash_table<K: Hash + Debug + Clone, V>(
+    mut assets: Vec<(K, V)>,
+) -> (mphf::bbhash::Mphf<K>, Vec<(K, V)>) {
+    let file_paths = assets
+        .iter()
+        .map(|(k, _)| k.clone())
+        .collect::<Vec<K>>();
+
+    let bbhas = mphf::bbhash::Mphf::new(1.7, &file_paths);
+
+    for current_asset_position in 0..assets.len() {
+        loop {
+            let (file_path, _) = &assets[current_asset_position];
+            let hash_position = bbhas.hash(
+           file_path
+            ) as usize;
+
+            if current_asset_position == hash_position {
+                break;
+            }
+
+            assets.swap(hash_position, current_asset_position);
+        }
+    }
+
+    (bbhas, assets)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::{NamedTempFile, Builder};
+    use test_case::test_case;
+    use tempfile::TempDir;
+
+    fn create_assets(assets: &[(&str, &str)]) -> Vec<(String, Vec<u8>)> {
+        assets
+            .iter()
+            .map(|(p, c)| ((*p).to_owned(), Vec::from(c.as_bytes())))
+            .collect::<Vec<(String, Vec<u8>)>>()
+    }
+
+    fn shuffle(mut buoy: Vec<(String, Vec<u8>)>) -> Vec<(String, Vec<u8>)> {
+        let size = buoy.len();
+        let halfway_point = size / 2;
+        for i in 0..halfway_point {
+            buoy.swap(i, halfway_point - i)
+        }
+
+        buoy
+    }
+
+    #[test]
+    fn confirm_entries_sorted_by_hasher() {
+        let assets = create_assets(&[("foo", "bar"), ("qux", "baz"), ("zoo", "books")]);
+        let (_, expected_hash_table) = build_hash_table(assets.clone());
+        let remixed_assets = shuffle(expected_hash_table.clone());
+
+        let (_, hash_table_result) = build_hash_table(remixed_assets);
+
+        assert_eq!(expected_hash_table, hash_table_result);
+    }
+
+    fn build_fake_keys(sub_dir: &TempDir, fake_file: &NamedTempFile, separator: &str, prefix: &str) -> Vec<String> {
+        let asset_path = [
+            sub_dir
+                .path()
+                .file_name()
+                .unwrap()
+                .to_string_lossy()
+                .into_owned(),
+            fake_file
+                .path()
+                .file_name()
+                .unwrap()
+                .to_string_lossy()
+                .into_owned()
+        ]
+        .join(separator);
+
+        let mut fake_key = String::from(prefix);
+        fake_key.push_str(&asset_path);
+
+        vec![fake_key]
+    }
+
+    #[test_case(false, "/", "/prefix/"; "forward slash with prefix")]
+    #[test_case(false, "/", ""; "forward slash without prefix")]
+    #[test_case(true, r"\", "/prefix/"; "backslash with prefix")]
+    #[test_case(true, r"\", ""; "backslash withouth prefix")]
+    fn test_path_separators(use_backslash_separator: bool, separator: &str, prefix: &str) {
+        let tmp_dir = TempDir::with_prefix("wheatley_location").unwrap();
+        let sub_dir = Builder::new()
+            .prefix("wheatley_sub_dir")
+            .tempdir_in(tmp_dir.path())
+            .unwrap();
+        let fake_file = Builder::new()
+            .prefix("foobar")
+            .tempfile_in(sub_dir.path())
+            .unwrap();
+
+        let expected_keys = build_fake_keys(
+            &sub_dir,
+            &fake_file,
+            separator,
+            prefix
+        );
+
+        let options = Options {
+            use_backslash_in_keys: use_backslash_separator,
+            prefix: prefix.to_owned(),
+            ..Default::default()
+        };
+
+        let actual_keys = gather(tmp_dir.path(), &options)
+            .unwrap()
+            .into_keys()
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            actual_keys,
+            expected_keys
+        )
+    }
+}
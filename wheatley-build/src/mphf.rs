@@ -36,12 +36,77 @@ pub mod bbhash {
     use std::hash::Hasher;
     use std::marker::PhantomData;
 
+    // Must match the seed used by the runtime in `wheatley::mphf`.
+    const FINGERPRINT_SEED: u64 = 0x9E37_79B9_7F4A_7C15;
+
+    /// Feeds integers to wyhash as little-endian bytes, where `Hash` would
+    /// write them in the byte order of the machine. A silo built on one
+    /// machine then finds its keys on a target of the other endianness.
+    /// `usize` and `isize` are widened to 64 bits for the same reason.
+    struct LittleEndian(wyhash::WyHash);
+
+    impl LittleEndian {
+        fn with_seed(seed: u64) -> Self {
+            Self(wyhash::WyHash::with_seed(seed))
+        }
+    }
+
+    impl Hasher for LittleEndian {
+        fn finish(&self) -> u64 {
+            self.0.finish()
+        }
+
+        fn write(&mut self, bytes: &[u8]) {
+            self.0.write(bytes)
+        }
+
+        fn write_u16(&mut self, i: u16) {
+            self.write(&i.to_le_bytes())
+        }
+
+        fn write_u32(&mut self, i: u32) {
+            self.write(&i.to_le_bytes())
+        }
+
+        fn write_u64(&mut self, i: u64) {
+            self.write(&i.to_le_bytes())
+        }
+
+        fn write_u128(&mut self, i: u128) {
+            self.write(&i.to_le_bytes())
+        }
+
+        fn write_usize(&mut self, i: usize) {
+            self.write(&(i as u64).to_le_bytes())
+        }
+
+        fn write_i16(&mut self, i: i16) {
+            self.write(&i.to_le_bytes())
+        }
+
+        fn write_i32(&mut self, i: i32) {
+            self.write(&i.to_le_bytes())
+        }
+
+        fn write_i64(&mut self, i: i64) {
+            self.write(&i.to_le_bytes())
+        }
+
+        fn write_i128(&mut self, i: i128) {
+            self.write(&i.to_le_bytes())
+        }
+
+        fn write_isize(&mut self, i: isize) {
+            self.write(&(i as i64).to_le_bytes())
+        }
+    }
+
     fn fold(v: u64) -> u32 {
         ((v & 0xFFFFFFFF) as u32) ^ ((v >> 32) as u32)
     }
 
     fn hash_with_seed<T: Hash + ?Sized>(iter: u64, v: &T) -> u64 {
-        let mut state = wyhash::WyHash::with_seed(1 << (iter + iter));
+        let mut state = LittleEndian::with_seed(1 << (iter + iter));
         v.hash(&mut state);
         state.finish()
     }
@@ -59,13 +124,21 @@ pub mod bbhash {
         // https://lemire.me/blog/2016/06/27/a-fast-alternative-to-the-modulo-reduction/
         if n < (1 << 32) {
             let h = hash_with_seed32(iter, v);
-            fastmod(h, n as u32) as u64
+            fastmod(h, n as u32)
         } else {
             let h = hash_with_seed(iter, v);
             h % n
         }
     }
 
+    /// Hash `item` with a seed independent of the levels. The runtime
+    /// compares it against the query's fingerprint to reject unknown keys.
+    pub fn fingerprint<T: Hash + ?Sized>(item: &T) -> u64 {
+        let mut state = LittleEndian::with_seed(FINGERPRINT_SEED);
+        item.hash(&mut state);
+        state.finish()
+    }
+
     /// A minimal perfect hash function over a set of objects of type `T`.
     #[derive(Clone, Debug)]
     pub struct Mphf<T> {
@@ -138,7 +211,7 @@ pub mod bbhash {
                 for i in 0..bv.num_words() {
                     let v = bv.get_word(i);
 
-                    if i % 8 == 0 {
+                    if i.is_multiple_of(8) {
                         rank.push(pop)
                     }
 
@@ -165,7 +238,7 @@ pub mod bbhash {
 
             // Add rank of final word up to hash
             let final_word = bv.get_word(idx / 64);
-            if idx % 64 > 0 {
+            if !idx.is_multiple_of(64) {
                 rank += (final_word << (64 - (idx % 64))).count_ones() as u64;
             }
             rank
@@ -224,6 +297,19 @@ pub mod bbhash {
         }
     }
 
+    #[cfg(test)]
+    mod endianness_tests {
+        use super::*;
+
+        #[test]
+        fn hash_integers_as_little_endian_bytes() {
+            let mut state = wyhash::WyHash::with_seed(FINGERPRINT_SEED);
+            state.write(&[4, 3, 2, 1]);
+
+            assert_eq!(fingerprint(&0x0102_0304_u32), state.finish());
+        }
+    }
+
     // #[cfg(test)]
     // mod tests {
     //
@@ -416,21 +502,6 @@ mod bitvector {
         pub vector: Box<[u64]>,
     }
 
-    impl quote::ToTokens for BitVector {
-        fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
-            let bits = self.bits;
-            let vector = self.vector.iter();
-            tokens.extend(quote::quote! {
-                (
-                    #bits,
-                    &[ #(#vector),* ]
-                )
-            })
-
-            // tokens.append()
-        }
-    }
-
     impl core::clone::Clone for BitVector {
         fn clone(&self) -> Self {
             Self {
@@ -503,7 +574,7 @@ mod bitvector {
         /// This method is averagely faster than `self.len() > 0`.
         #[allow(dead_code)]
         pub fn is_empty(&self) -> bool {
-            return self.vector.iter().all(|x| *x == 0);
+            self.vector.iter().all(|x| *x == 0)
         }
 
         /// If `bit` belongs to set, return `true`, else return `false`.
@@ -627,7 +698,7 @@ mod bitvector {
     }
 
     fn u64s(elements: u64) -> u64 {
-        (elements + 63) / 64
+        elements.div_ceil(64)
     }
 
     fn word_offset(index: u64) -> (u64, u64) {
@@ -0,0 +1,130 @@
+use crate::enum_impl::write_enum_impl;
+use crate::pairing::declare_variants;
+use heck::ToSnakeCase;
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use std::path::PathBuf;
+use syn::parse::{Parse, ParseStream};
+use syn::punctuated::Punctuated;
+use syn::{braced, parse_quote, Attribute, DeriveInput, Ident, LitStr, Token, Visibility};
+
+/// The options `asset_enum!` takes, forwarded to `#[wheatley(...)]`.
+const OPTIONS: [&str; 3] = ["location", "schema", "format"];
+
+/// `#[attributes] vis enum Name { option: "value", ... }`
+pub struct AssetEnum {
+    attrs: Vec<Attribute>,
+    vis: Visibility,
+    ident: Ident,
+    options: Vec<(Ident, LitStr)>,
+}
+
+impl Parse for AssetEnum {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let attrs = input.call(Attribute::parse_outer)?;
+        let vis = input.parse()?;
+        input.parse::<Token![enum]>()?;
+        let ident = input.parse()?;
+
+        let content;
+        braced!(content in input);
+
+        let options = Punctuated::<(Ident, LitStr), Token![,]>::parse_terminated_with(&content, |option| {
+            let name = option.parse::<Ident>()?;
+            if !OPTIONS.iter().any(|known| name == known) {
+                return Err(syn::Error::new(
+                    name.span(),
+                    format!("Unknown asset_enum option, expected one of {}", OPTIONS.join(", ")),
+                ));
+            }
+            option.parse::<Token![:]>()?;
+
+            Ok((name, option.parse()?))
+        })?;
+
+        Ok(Self {
+            attrs,
+            vis,
+            ident,
+            options: options.into_iter().collect(),
+        })
+    }
+}
+
+/// Declare the enum with a variant per file in its location and derive
+/// `Wheatley` for it. Each subdirectory becomes a variant holding the enum
+/// generated for it in a module named after the parent, e.g.
+/// `Books::SuzanneLang(books::SuzanneLang)` for `examples/books/suzanne_lang`.
+pub fn write_asset_enum(asset_enum: AssetEnum) -> syn::Result<TokenStream> {
+    let AssetEnum {
+        attrs,
+        vis,
+        ident,
+        options,
+    } = asset_enum;
+
+    // Without a location the assets live in a directory named after the
+    // enum, as with the derive
+    let location = options
+        .iter()
+        .find(|(name, _)| name == "location")
+        .map(|(_, location)| location.value())
+        .unwrap_or_else(|| ident.to_string().to_lowercase());
+
+    let manifest_dir = PathBuf::from(std::env::var("CARGO_MANIFEST_DIR").unwrap());
+    let module = format_ident!("{}", ident.to_string().to_snake_case());
+    let variants = declare_variants(&manifest_dir.join(&location), &module, &ident)?;
+
+    if variants.is_empty() {
+        return Err(syn::Error::new_spanned(
+            &ident,
+            format!("No file in {location} backs a variant of `{ident}`"),
+        ));
+    }
+
+    let options = options
+        .iter()
+        .filter(|(option, _)| option != "location")
+        .map(|(option, value)| quote!(, #option = #value));
+
+    let declaration = quote! {
+        #(#attrs)*
+        #vis enum #ident {
+            #(#variants),*
+        }
+    };
+    let ast: DeriveInput = parse_quote! {
+        #[wheatley(location = #location #(#options)*)]
+        #declaration
+    };
+
+    let mut tokens = declaration;
+    tokens.extend(write_enum_impl(ast)?);
+
+    Ok(tokens)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_asset_enum() {
+        let asset_enum: AssetEnum = parse_quote! {
+            #[derive(Debug)]
+            pub enum Books { location: "examples/books", format: "toml" }
+        };
+
+        assert_eq!(asset_enum.ident, "Books");
+        assert_eq!(asset_enum.attrs.len(), 1);
+        assert_eq!(asset_enum.options.len(), 2);
+        assert_eq!(asset_enum.options[0].1.value(), "examples/books");
+    }
+
+    #[test]
+    fn reject_unknown_option() {
+        let asset_enum = syn::parse_str::<AssetEnum>(r#"enum Books { locaton: "examples/books" }"#);
+
+        assert!(asset_enum.is_err());
+    }
+}
@@ -1,5 +1,5 @@
 use cfg_if::cfg_if;
-use std::path::PathBuf;
+use wheatley_build::Encoding;
 
 #[cfg(feature = "gzip")]
 fn compress_with_gzip(asset: &mut Vec<u8>) -> Vec<u8> {
@@ -65,3 +65,19 @@ pub fn compress_assets(hash_table: &mut [(String, Vec<u8>)]) {
         *asset = compressor(asset);
     }
 }
+
+pub fn get_encoding() -> Encoding {
+    cfg_if! {
+        if #[cfg(feature = "gzip")] {
+            Encoding::Gzip
+        } else if #[cfg(feature = "br")] {
+            Encoding::Brotli
+        } else if #[cfg(feature = "zstd")] {
+            Encoding::Zstd
+        } else if #[cfg(feature = "snap")] {
+            Encoding::Snappy
+        } else {
+            Encoding::Identity
+        }
+    }
+}
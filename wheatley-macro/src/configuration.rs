@@ -1,4 +1,3 @@
-use super::GlobGroup;
 use std::collections::HashMap;
 use std::path::Component;
 use std::path::PathBuf;
@@ -6,10 +5,11 @@ use syn::{
     parse2, 
     ExprArray, 
     LitStr,
-    Expr::Lit,
     Lit::Str
 };
 use proc_macro2::TokenStream;
+use wheatley_build::Options;
+use crate::keys::KeyConfig;
 
 #[derive(Debug, Default)]
 pub struct Config {
@@ -17,7 +17,31 @@ pub struct Config {
     pub prefix: String,
     pub use_backslash_in_keys: bool,
     pub ignore_globs: Vec<String>,
-    pub asset_manifest: Vec<String>,
+    pub key: Option<KeyConfig>,
+    pub markdown: Option<Markdown>,
+    /// The module to declare the silo in along with a constant per file.
+    pub constants: Option<syn::Ident>,
+    /// The key of the file `asset!` picks out of the location.
+    pub path: Option<String>,
+}
+
+/// What `embed_assets!` keeps of Markdown files in place of their contents.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Markdown {
+    /// The text after the front matter.
+    Body,
+    /// The text after the front matter rendered to HTML.
+    Html,
+}
+
+impl Config {
+    pub fn options(&self) -> Options {
+        Options {
+            prefix: self.prefix.clone(),
+            use_backslash_in_keys: self.use_backslash_in_keys,
+            ignore_globs: self.ignore_globs.clone(),
+        }
+    }
 }
 
 pub fn build_config(ast: proc_macro2::TokenStream) -> Config {
@@ -36,10 +60,9 @@ pub fn build_config(ast: proc_macro2::TokenStream) -> Config {
     let prefix = token_index
         .get("prefix")
         .map(|token| {
-            token
-                .to_string()
-                .parse::<String>()
-                .expect("Config value for prepend_slash is a string type")
+            parse2::<LitStr>(token.clone().into())
+                .expect("Config value for prefix is a string type")
+                .value()
         })
         .unwrap_or_default();
 
@@ -135,12 +158,46 @@ pub fn build_config(ast: proc_macro2::TokenStream) -> Config {
         })
         .collect::<PathBuf>();
 
+    // `key: u32 from "sprite_{id}.png"` lands in the index as two pairs
+    let key = match (token_index.get("key"), token_index.get("from")) {
+        (Some(ty), Some(pattern)) => Some(KeyConfig {
+            ty: parse2::<syn::Ident>(ty.clone().into()).expect("Config value for key is an integer type"),
+            pattern: parse2::<LitStr>(pattern.clone().into())
+                .expect("Key pattern is a string such as \"sprite_{id}.png\""),
+        }),
+        (None, None) => None,
+        _ => panic!("Keys are configured as `key: u32 from \"sprite_{{id}}.png\"`"),
+    };
+
+    let markdown = token_index.get("markdown").map(|token| {
+        let markdown = parse2::<LitStr>(token.clone().into()).expect("Config value for markdown is a string type");
+
+        match markdown.value().as_str() {
+            "body" => Markdown::Body,
+            "html" => Markdown::Html,
+            other => panic!("Config value for markdown is \"body\" or \"html\", not {other:?}"),
+        }
+    });
+
+    let constants = token_index.get("constants").map(|token| {
+        parse2::<syn::Ident>(token.clone().into()).expect("Config value for constants is a module name")
+    });
+
+    let path = token_index.get("path").map(|token| {
+        parse2::<LitStr>(token.clone().into())
+            .expect("Config value for path is a string type")
+            .value()
+    });
+
     Config {
         location,
         ignore_globs,
-        asset_manifest: vec![],
         prefix,
         use_backslash_in_keys,
+        key,
+        markdown,
+        constants,
+        path,
     }
 }
 
@@ -194,4 +251,47 @@ mod tests {
 
         assert_eq!(actual_location, expected_location);
     }
+
+    #[test]
+    fn read_key_pattern() {
+        let ast = quote! {
+            location: "./assets",
+            key: u32 from "sprite_{id}.png",
+        };
+
+        let key = build_config(ast).key.unwrap();
+
+        assert_eq!(key.ty, "u32");
+        assert_eq!(key.pattern.value(), "sprite_{id}.png");
+    }
+
+    #[test]
+    fn read_markdown_output() {
+        let ast = quote! {
+            location: "./assets",
+            markdown: "html",
+        };
+
+        assert_eq!(build_config(ast).markdown, Some(Markdown::Html));
+    }
+
+    #[test]
+    fn read_constants_module() {
+        let ast = quote! {
+            location: "./assets",
+            constants: assets,
+        };
+
+        assert_eq!(build_config(ast).constants.unwrap(), "assets");
+    }
+
+    #[test]
+    fn read_asset_path() {
+        let ast = quote! {
+            location: "./assets",
+            path: "css/main.css",
+        };
+
+        assert_eq!(build_config(ast).path.as_deref(), Some("css/main.css"));
+    }
 }
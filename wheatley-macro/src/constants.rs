@@ -0,0 +1,176 @@
+use crate::schema::Errors;
+use heck::{ToShoutySnakeCase, ToSnakeCase};
+use proc_macro2::{Span, TokenStream};
+use quote::{format_ident, quote};
+use std::collections::{BTreeMap, BTreeSet};
+use syn::Ident;
+
+/// The files of one directory of a silo and its subdirectories.
+#[derive(Default)]
+struct Directory {
+    /// The path of the directory within the silo, e.g. `dr_seuss/`.
+    path: String,
+    /// Constant names mapped to the position and path of their file.
+    files: BTreeMap<String, (usize, String)>,
+    subdirectories: BTreeMap<String, Directory>,
+}
+
+/// A module tree mirroring the directories of a silo, with a constant per
+/// file named after it, e.g. `dr_seuss::CAT_IN_THE_HAT_TXT`. The constants
+/// are of the `Handle` type declared beside the tree. `paths` are the keys
+/// of the entries in order, each starting with `prefix`.
+pub fn write_constants<'a>(paths: impl Iterator<Item = &'a str>, prefix: &str) -> syn::Result<TokenStream> {
+    let mut errors = Errors::default();
+    let mut root = Directory::default();
+    let mut clashes = BTreeSet::new();
+
+    for (position, path) in paths.enumerate() {
+        let relative_path = path.strip_prefix(prefix).unwrap_or(path);
+        let mut segments = relative_path.split(['/', '\\']).filter(|segment| !segment.is_empty()).collect::<Vec<_>>();
+        let file_name = segments.pop().unwrap_or_default();
+
+        let mut directory = &mut root;
+        for segment in segments {
+            let module = segment.to_snake_case();
+            let path = format!("{}{segment}/", directory.path);
+            let subdirectory = directory.subdirectories.entry(module.clone()).or_insert_with(|| Directory {
+                path: path.clone(),
+                ..Default::default()
+            });
+
+            if subdirectory.path != path && clashes.insert((subdirectory.path.clone(), path.clone())) {
+                errors.push(syn::Error::new(
+                    Span::call_site(),
+                    format!("`{}` and `{path}` both make the module `{module}`", subdirectory.path),
+                ));
+            }
+            directory = subdirectory;
+        }
+
+        // The root module also declares the silo. `Handle` is safe, as
+        // constants are upper case.
+        let name = file_name.to_shouty_snake_case();
+        if directory.path.is_empty() && name == "SILO" {
+            errors.push(syn::Error::new(
+                Span::call_site(),
+                format!("`{path}` makes the constant `SILO`, which names the silo beside the constants"),
+            ));
+            continue;
+        }
+        if let Some((_, other)) = directory.files.insert(name.clone(), (position, path.to_owned())) {
+            errors.push(syn::Error::new(
+                Span::call_site(),
+                format!("`{other}` and `{path}` both make the constant `{name}`"),
+            ));
+        }
+    }
+
+    errors.finish()?;
+
+    write_directory(&root)
+}
+
+fn write_directory(directory: &Directory) -> syn::Result<TokenStream> {
+    let mut items = vec![];
+
+    // Every module below the root reaches the handle type of the root
+    if !directory.path.is_empty() {
+        items.push(quote!(use super::Handle;));
+    }
+
+    for (name, (position, path)) in &directory.files {
+        let constant = identifier(name)?;
+
+        items.push(quote! {
+            #[doc = concat!("`", #path, "`")]
+            pub const #constant: Handle = Handle(wheatley::Handle::new(#position));
+        });
+    }
+
+    for (name, subdirectory) in &directory.subdirectories {
+        let module = identifier(name)?;
+        let contents = write_directory(subdirectory)?;
+
+        items.push(quote! {
+            pub mod #module {
+                #contents
+            }
+        });
+    }
+
+    Ok(quote!(#(#items)*))
+}
+
+/// `name` as an identifier, with a leading underscore if it starts with a
+/// digit and escaped if it is a keyword.
+fn identifier(name: &str) -> syn::Result<Ident> {
+    let name = if name.starts_with(|character: char| character.is_ascii_digit()) {
+        format!("_{name}")
+    } else {
+        name.to_owned()
+    };
+
+    syn::parse_str::<Ident>(&name)
+        .or_else(|_| syn::parse_str::<Ident>(&format!("r#{name}")))
+        .map(|ident| format_ident!("{ident}"))
+        .map_err(|_| syn::Error::new(Span::call_site(), format!("`{name}` makes no Rust identifier")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn nest_constants_in_directory_modules() {
+        let paths = ["hardly_haunted.txt", "theodor_seuss_geisel/dr_seuss/cat_in_the_hat.txt"];
+
+        let constants = write_constants(paths.into_iter(), "").unwrap().to_string();
+
+        assert!(constants.contains("pub const HARDLY_HAUNTED_TXT : Handle = Handle (wheatley :: Handle :: new (0usize))"));
+        assert!(constants.contains("pub mod theodor_seuss_geisel { use super :: Handle ; pub mod dr_seuss {"));
+        assert!(constants.contains("CAT_IN_THE_HAT_TXT : Handle = Handle (wheatley :: Handle :: new (1usize))"));
+    }
+
+    #[test]
+    fn strip_prefix_and_escape_names() {
+        let paths = ["/static/404.html", "/static/type/mod.rs"];
+
+        let constants = write_constants(paths.into_iter(), "/static/").unwrap().to_string();
+
+        assert!(constants.contains("pub const _404_HTML"));
+        assert!(constants.contains("pub mod r#type { use super :: Handle ;"));
+    }
+
+    #[test]
+    fn report_files_making_the_same_constant() {
+        let paths = ["cat-in-the-hat.txt", "cat_in_the_hat.txt"];
+
+        let error = write_constants(paths.into_iter(), "").unwrap_err();
+
+        assert_eq!(
+            error.to_string(),
+            "`cat-in-the-hat.txt` and `cat_in_the_hat.txt` both make the constant `CAT_IN_THE_HAT_TXT`"
+        );
+    }
+
+    #[test]
+    fn report_file_making_the_silo_constant() {
+        let paths = ["/static/silo", "/static/books/silo"];
+
+        let error = write_constants(paths.into_iter(), "/static/").unwrap_err();
+
+        assert_eq!(
+            error.to_string(),
+            "`/static/silo` makes the constant `SILO`, which names the silo beside the constants"
+        );
+    }
+
+    #[test]
+    fn report_directories_making_the_same_module() {
+        let paths = ["dr-seuss/cat_in_the_hat.txt", "dr_seuss/green_eggs_and_ham.txt", "dr_seuss/lorax.txt"];
+
+        let error = write_constants(paths.into_iter(), "").unwrap_err();
+
+        assert_eq!(error.to_string(), "`dr-seuss/` and `dr_seuss/` both make the module `dr_seuss`");
+    }
+}
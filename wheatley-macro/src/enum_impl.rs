@@ -0,0 +1,1027 @@
+use crate::formats::{self, Format};
+use crate::locales::Locales;
+use crate::pairing::{
+    declare_variants, gather_assets, gather_rows, gather_translations, gather_tree, pair_translations, pair_variants,
+    Asset, Nested, Variant,
+};
+use crate::schema::{Errors, Kind, Property, Schema, Source};
+use crate::templates::Template;
+use crate::values::{self, Embedder};
+use heck::{ToSnakeCase, ToUpperCamelCase};
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote, ToTokens};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::PathBuf;
+use syn::ext::IdentExt;
+use syn::punctuated::Punctuated;
+use syn::{parse_quote, DeriveInput, LitStr, Token, Visibility};
+use toml::Value;
+
+/// Options given through `#[wheatley(...)]` on a derived enum.
+#[derive(Clone)]
+pub struct EnumConfig {
+    pub location: PathBuf,
+    pub allow_extra_files: bool,
+    /// Embed the files as they are, without reading properties from them.
+    pub raw: bool,
+    pub schema: Option<(PathBuf, LitStr)>,
+    pub format: Option<Format>,
+    /// A CSV file whose rows back the variants, and its key column.
+    pub csv: Option<(PathBuf, String)>,
+    /// Set when the location holds a directory of files per locale.
+    pub default_locale: Option<LitStr>,
+    /// String properties holding message templates.
+    pub templates: Vec<LitStr>,
+    /// The visibility of the generated methods and constants.
+    pub vis: Visibility,
+    /// Method names for properties, keyed by the snake_case property.
+    pub renames: Vec<(syn::Ident, syn::Ident)>,
+}
+
+impl EnumConfig {
+    fn from_attributes(ast: &DeriveInput) -> syn::Result<Self> {
+        let mut location = None;
+        let mut allow_extra_files = false;
+        let mut raw = false;
+        let mut schema = None;
+        let mut format = None;
+        let mut csv = None;
+        let mut key = None;
+        let mut default_locale = None;
+        let mut templates = Vec::new();
+        let mut vis = None;
+        let mut renames = Vec::new();
+
+        for attribute in ast.attrs.iter().filter(|a| a.path().is_ident("wheatley")) {
+            attribute.parse_nested_meta(|meta| {
+                if meta.path.is_ident("location") {
+                    location = Some(meta.value()?.parse::<LitStr>()?.value());
+                    Ok(())
+                } else if meta.path.is_ident("allow_extra_files") {
+                    allow_extra_files = true;
+                    Ok(())
+                } else if meta.path.is_ident("raw") {
+                    raw = true;
+                    Ok(())
+                } else if meta.path.is_ident("schema") {
+                    schema = Some(meta.value()?.parse::<LitStr>()?);
+                    Ok(())
+                } else if meta.path.is_ident("format") {
+                    let name = meta.value()?.parse::<LitStr>()?;
+                    format = Some(Format::parse(&name.value()).ok_or_else(|| {
+                        syn::Error::new(name.span(), "Unknown format, expected one of toml, json, yaml, ron or markdown")
+                    })?);
+                    Ok(())
+                } else if meta.path.is_ident("csv") {
+                    csv = Some(meta.value()?.parse::<LitStr>()?.value());
+                    Ok(())
+                } else if meta.path.is_ident("key") {
+                    key = Some(meta.value()?.parse::<LitStr>()?.value());
+                    Ok(())
+                } else if meta.path.is_ident("default_locale") {
+                    default_locale = Some(meta.value()?.parse::<LitStr>()?);
+                    Ok(())
+                } else if meta.path.is_ident("template") {
+                    if !cfg!(feature = "alloc") {
+                        return Err(meta.error("Templates build a `String`, enable the `alloc` or `std` feature of wheatley"));
+                    }
+                    templates.push(meta.value()?.parse::<LitStr>()?);
+                    Ok(())
+                } else if meta.path.is_ident("vis") {
+                    vis = Some(meta.value()?.parse::<LitStr>()?.parse::<Visibility>()?);
+                    Ok(())
+                } else if meta.path.is_ident("rename") {
+                    meta.parse_nested_meta(|rename| {
+                        let property = rename.path.require_ident()?.clone();
+                        let method = rename.value()?.parse::<LitStr>()?.parse::<syn::Ident>()?;
+                        renames.push((property, method));
+                        Ok(())
+                    })
+                } else {
+                    Err(meta.error("Unknown wheatley option"))
+                }
+            })?;
+        }
+
+        // Without a location the assets live in a directory named after the
+        // enum, e.g. `cars` for `enum Cars`
+        let location = location.unwrap_or_else(|| ast.ident.to_string().to_lowercase());
+
+        let manifest_dir = PathBuf::from(std::env::var("CARGO_MANIFEST_DIR").unwrap());
+
+        let csv = match (csv, key) {
+            (Some(csv), Some(key)) => Some((manifest_dir.join(csv), key)),
+            (None, None) => None,
+            _ => {
+                return Err(syn::Error::new_spanned(
+                    &ast.ident,
+                    "CSV backed enums need both `csv` and `key`, e.g. #[wheatley(csv = \"books.csv\", key = \"title\")]",
+                ))
+            }
+        };
+
+        if raw {
+            let structured = [
+                ("csv", csv.is_some()),
+                ("schema", schema.is_some()),
+                ("format", format.is_some()),
+                ("template", !templates.is_empty()),
+            ];
+            if let Some((option, _)) = structured.iter().find(|(_, set)| *set) {
+                return Err(syn::Error::new_spanned(
+                    &ast.ident,
+                    format!("Raw enums have no properties, so `{option}` does not apply"),
+                ));
+            }
+        }
+
+        if let (Some(_), Some(default_locale)) = (&csv, &default_locale) {
+            return Err(syn::Error::new(
+                default_locale.span(),
+                "CSV backed enums cannot be localised",
+            ));
+        }
+
+        Ok(Self {
+            location: manifest_dir.join(location),
+            allow_extra_files,
+            raw,
+            schema: schema.map(|schema| (manifest_dir.join(schema.value()), schema)),
+            format,
+            csv,
+            default_locale,
+            templates,
+            vis: vis.unwrap_or_else(|| parse_quote!(pub)),
+            renames,
+        })
+    }
+}
+
+/// The method reading `property`: the name `renames` gives it, or the
+/// property as an identifier. The error explains why neither fits.
+fn method_ident(property: &str, renames: &[(syn::Ident, syn::Ident)]) -> Result<syn::Ident, String> {
+    let snake_case = property.to_snake_case();
+
+    match renames.iter().find(|(renamed, _)| renamed.unraw() == snake_case) {
+        Some((_, method)) => Ok(method.clone()),
+        None => values::field_ident(property)
+            .map_err(|reason| format!("{reason}, name its method with #[wheatley(rename({snake_case} = \"...\"))]")),
+    }
+}
+
+/// Methods generated for every enum, which properties cannot share names with.
+const GENERATED_METHODS: [&str; 4] = ["contents", "iter", "name", "path"];
+
+/// Methods generated for enums of Markdown files.
+const MARKDOWN_METHODS: [&str; 2] = ["body", "html"];
+
+/// What every enum of a tree shares with the derived one at its root, so
+/// that variants holding nested enums can forward to them.
+struct Tree {
+    /// The enum at the root, which names the companion structs.
+    root: syn::Ident,
+    schema: Schema,
+    /// The placeholders of every template property in order of first use.
+    placeholders: BTreeMap<String, Vec<syn::Ident>>,
+    /// Whether every file of the tree is Markdown.
+    markdown: bool,
+}
+
+/// A match arm: its pattern and the asset it reads, or none for a variant
+/// holding a nested enum, bound to `inner`.
+type Arm<'a> = (TokenStream, Option<&'a Asset>);
+
+pub fn write_enum_impl(ast: DeriveInput) -> syn::Result<TokenStream> {
+    let config = EnumConfig::from_attributes(&ast)?;
+
+    write_level(&ast, &config, None, "").map(|(tokens, _)| tokens)
+}
+
+/// Implement `ast`, the root of a tree or, given the `tree` read for its
+/// root, one of the enums generated for a subdirectory. `prefix` is the
+/// subdirectory within the root location, e.g. `theodor_seuss_geisel/`.
+/// Also returns every name `TryFrom<&str>` accepts, down to those of the
+/// nested enums.
+fn write_level(
+    ast: &DeriveInput,
+    config: &EnumConfig,
+    tree: Option<&Tree>,
+    prefix: &str,
+) -> syn::Result<(TokenStream, Vec<String>)> {
+    let target = &ast.ident;
+    let is_root = tree.is_none();
+
+    let locales = match &config.default_locale {
+        Some(default) => Some(Locales::discover(&config.location, default)?),
+        None => None,
+    };
+
+    let mut asset_register = match (&config.csv, &locales) {
+        (Some((csv, key)), _) => gather_rows(csv, key, target)?,
+        (None, Some(locales)) => gather_assets(
+            config,
+            &config.location.join(&locales.default),
+            &format!("{}/", locales.default),
+            target,
+        )?,
+        (None, None) => gather_assets(config, &config.location, prefix, target)?,
+    };
+    // Files of nested enums are named from the root location, e.g.
+    // `deborah_diesen/pout_pout_fish`
+    for asset in asset_register.values_mut() {
+        asset.name.insert_str(0, prefix);
+    }
+
+    let syn::Data::Enum(target_enum) = &ast.data else {
+        panic!("Can only derive an Enum")
+    };
+
+    let (impl_generics, type_generics, where_clause) = ast.generics.split_for_impl();
+
+    let (variants, nested) = pair_variants(ast, target_enum, &asset_register, config)?;
+
+    let registers = match &locales {
+        Some(locales) => gather_translations(locales, config, target)?,
+        None => BTreeMap::new(),
+    };
+    let translations = pair_translations(&registers, &variants, config, target)?;
+
+    let root_tree;
+    let tree = match tree {
+        Some(tree) => tree,
+        None => {
+            root_tree = read_tree(ast, config, &variants, &translations, &nested)?;
+            &root_tree
+        }
+    };
+
+    // Localised enums read the asset of each variant in the locale asked
+    // for, falling back along the locale chain where it has none
+    let locale_enum = format_ident!("{target}Locale");
+    let (scrutinee, locale_parameter, arms) = match &locales {
+        None => {
+            let files = variants.iter().map(|variant| {
+                let ident = variant.ident;
+                (quote!(Self::#ident), Some(variant.asset))
+            });
+            let directories = nested.iter().map(|nested| {
+                let ident = nested.ident;
+                (quote!(Self::#ident(inner)), None)
+            });
+
+            (quote!(self), TokenStream::new(), files.chain(directories).collect::<Vec<Arm>>())
+        }
+        Some(locales) => {
+            let mut arms: Vec<Arm> = Vec::new();
+            for (index, variant) in variants.iter().enumerate() {
+                let ident = variant.ident;
+                for (code, locale) in &locales.codes {
+                    let asset = locales
+                        .chain(code)
+                        .into_iter()
+                        .find_map(|code| match translations.get(code) {
+                            Some(assets) => assets[index],
+                            None => Some(variant.asset),
+                        })
+                        .unwrap();
+
+                    arms.push((quote!((Self::#ident, #locale_enum::#locale)), Some(asset)));
+                }
+            }
+
+            (quote!((self, locale)), quote!(, locale: #locale_enum), arms)
+        }
+    };
+    let patterns = arms.iter().map(|(pattern, _)| pattern).collect::<Vec<_>>();
+
+    let mut fragments = vec![];
+
+    let mut embedder = Embedder::new(ast.vis.clone());
+
+    // Enums of Markdown files only also get the body, and its HTML
+    let markdown_methods = if tree.markdown {
+        MARKDOWN_METHODS.as_slice()
+    } else {
+        &[]
+    };
+
+    // Nested enums sit in a module below their parent, which must still
+    // reach their methods
+    let vis = match &config.vis {
+        Visibility::Inherited if !is_root => parse_quote!(pub(super)),
+        Visibility::Restricted(restricted) if !is_root && restricted.path.is_ident("self") => parse_quote!(pub(super)),
+        vis => vis.clone(),
+    };
+
+    let mut methods: BTreeMap<String, &String> = BTreeMap::new();
+    for (property, details) in tree.schema.properties.iter() {
+        let method = method_ident(property, &config.renames)
+            .map_err(|reason| syn::Error::new_spanned(target, format!("Property {reason}")))?;
+        let name = method.unraw().to_string();
+
+        if GENERATED_METHODS.contains(&name.as_str()) || markdown_methods.contains(&name.as_str()) {
+            return Err(syn::Error::new_spanned(
+                target,
+                format!("Property `{property}` clashes with the generated `{name}()`, name its method with #[wheatley(rename({name} = \"...\"))]"),
+            ));
+        }
+        if let Some(other) = methods.insert(name.clone(), property) {
+            return Err(syn::Error::new_spanned(
+                target,
+                format!("Properties `{other}` and `{property}` both make the method `{name}()`"),
+            ));
+        }
+
+        if let Some(placeholders) = tree.placeholders.get(property) {
+            let template_method = write_template_method(
+                property,
+                details,
+                &method,
+                placeholders,
+                &arms,
+                &scrutinee,
+                &locale_parameter,
+            );
+            fragments.push(quote!(#vis #template_method));
+            continue;
+        }
+
+        // Tables become companion structs such as `CarsEngine`
+        let struct_name = format!("{}{}", tree.root, property.to_upper_camel_case());
+
+        let rust_type = embedder
+            .rust_type(&details.kind, &struct_name)
+            .map_err(|reason| syn::Error::new_spanned(target, format!("Property `{property}` {reason}")))?;
+
+        // Tables are returned by reference to a promoted constant rather
+        // than copied on every call
+        let is_table = matches!(details.kind, Kind::Table(_));
+        let rust_type = if is_table { quote!(&'static #rust_type) } else { rust_type };
+
+        let values = arms.iter().map(|(_, asset)| {
+            let Some(asset) = asset else {
+                return quote!(inner.#method());
+            };
+            let value = asset.table.get(property).map(|value| {
+                let value = values::literal(value, &details.kind, &struct_name);
+                if is_table {
+                    quote!(&#value)
+                } else {
+                    value
+                }
+            });
+
+            match (details.required, value) {
+                (true, value) => quote!(#value),
+                (false, Some(value)) => quote!(::core::option::Option::Some(#value)),
+                (false, None) => quote!(::core::option::Option::None),
+            }
+        });
+
+        let return_type = if details.required {
+            rust_type
+        } else {
+            quote!(::core::option::Option<#rust_type>)
+        };
+
+        fragments.push(quote! {
+            #vis const fn #method(&self #locale_parameter) -> #return_type {
+                match #scrutinee {
+                    #(#patterns => #values),*
+                }
+            }
+        });
+    }
+
+    if let Some((property, _)) = config.renames.iter().find(|(property, _)| {
+        !tree.schema.properties.keys().any(|key| property.unraw() == key.to_snake_case())
+    }) {
+        return Err(syn::Error::new_spanned(
+            property,
+            format!("No property is called `{}` in snake_case", property.unraw()),
+        ));
+    }
+
+    if tree.markdown {
+        fragments.push(write_markdown_methods(&vis, &scrutinee, &locale_parameter, &arms));
+    }
+
+    // The companion structs are declared once, beside the root
+    let structs = if is_root { embedder.structs } else { vec![] };
+    let locale_declaration = match &locales {
+        Some(locales) => locales.write_enum(&locale_enum, &ast.vis),
+        None => TokenStream::new(),
+    };
+    let variant_idents = variants.iter().map(|variant| variant.ident).collect::<Vec<_>>();
+    let names = variants.iter().map(|variant| &variant.name).collect::<Vec<_>>();
+    let nested_idents = nested.iter().map(|nested| nested.ident).collect::<Vec<_>>();
+    let nested_types = nested.iter().map(|nested| nested.ty).collect::<Vec<_>>();
+    let forward = |method: TokenStream| {
+        arms.iter()
+            .map(|(_, asset)| match asset {
+                Some(_) => None,
+                None => Some(quote!(inner.#method)),
+            })
+            .collect::<Vec<_>>()
+    };
+    let paths = arms
+        .iter()
+        .zip(forward(quote!(path())))
+        .map(|((_, asset), forwarded)| forwarded.unwrap_or_else(|| {
+            let path = &asset.unwrap().path;
+            quote!(#path)
+        }));
+
+    // Raw enums may hold binary files, so they have bytes and, for files
+    // that are UTF-8, a string instead
+    if !config.raw {
+        let contents = arms
+            .iter()
+            .zip(forward(quote!(contents())))
+            .map(|((_, asset), forwarded)| forwarded.unwrap_or_else(|| asset.unwrap().contents.clone()));
+        fragments.push(quote! {
+            /// The file, or CSV row, backing this variant exactly as it is
+            /// on disk.
+            #vis const fn contents(&self #locale_parameter) -> &'static str {
+                match #scrutinee {
+                    #(#patterns => #contents),*
+                }
+            }
+        });
+    }
+    if config.raw {
+        let bytes = arms
+            .iter()
+            .zip(forward(quote!(bytes())))
+            .map(|((_, asset), forwarded)| forwarded.unwrap_or_else(|| asset.unwrap().bytes.clone()));
+        let strs = arms.iter().zip(forward(quote!(as_str()))).map(|((_, asset), forwarded)| {
+            forwarded.unwrap_or_else(|| {
+                let asset = asset.unwrap();
+                let contents = &asset.contents;
+
+                if asset.utf8 {
+                    quote!(::core::option::Option::Some(#contents))
+                } else {
+                    quote!(::core::option::Option::None)
+                }
+            })
+        });
+        fragments.push(quote! {
+            /// The bytes of the file backing this variant.
+            #vis const fn bytes(&self #locale_parameter) -> &'static [u8] {
+                match #scrutinee {
+                    #(#patterns => #bytes),*
+                }
+            }
+
+            /// The file backing this variant, or `None` if it is not UTF-8,
+            /// which is checked at compile time.
+            #vis const fn as_str(&self #locale_parameter) -> ::core::option::Option<&'static str> {
+                match #scrutinee {
+                    #(#patterns => #strs),*
+                }
+            }
+        });
+    }
+    let aliases = variants.iter().filter(|variant| !variant.aliases.is_empty()).map(|variant| {
+        let ident = variant.ident;
+        let aliases = &variant.aliases;
+        quote!(#(#aliases)|* => ::core::result::Result::Ok(Self::#ident),)
+    });
+    let count = variants.len();
+
+    // With nested enums, every file of the tree is listed where the variant
+    // holding its enum is declared
+    let all = if nested.is_empty() {
+        quote!(&[#(Self::#variant_idents),*])
+    } else {
+        let fills = target_enum.variants.iter().map(|variant| match &variant.fields {
+            syn::Fields::Unnamed(fields) => {
+                let ident = &variant.ident;
+                let ty = &fields.unnamed[0].ty;
+                quote! {
+                    let mut inner = 0;
+                    while inner < #ty::COUNT {
+                        all[index] = Self::#ident(#ty::ALL[inner]);
+                        index += 1;
+                        inner += 1;
+                    }
+                }
+            }
+            _ => {
+                let ident = &variant.ident;
+                quote! {
+                    all[index] = Self::#ident;
+                    index += 1;
+                }
+            }
+        });
+        let first = match target_enum.variants.first().map(|variant| (&variant.ident, &variant.fields)) {
+            Some((ident, syn::Fields::Unnamed(fields))) => {
+                let ty = &fields.unnamed[0].ty;
+                quote!(#target::#ident(#ty::ALL[0]))
+            }
+            Some((ident, _)) => quote!(#target::#ident),
+            None => unreachable!("nested enums have a variant"),
+        };
+
+        quote! {
+            &{
+                const FIRST: #target = #first;
+
+                let mut all = [FIRST; Self::COUNT];
+                let mut index = 0;
+                #(#fills)*
+                assert!(index == Self::COUNT);
+
+                all
+            }
+        }
+    };
+
+    let mut accepted = variants
+        .iter()
+        .flat_map(|variant| std::iter::once(variant.name.clone()).chain(variant.aliases.iter().map(LitStr::value)))
+        .collect::<Vec<_>>();
+
+    // Nested enums derive what their parent does, so that its derives find
+    // them implemented, and `Clone` and `Copy` so that `ALL` can be filled
+    // from theirs. Traits listed beside `Wheatley` never reach it.
+    let mut derives: Vec<syn::Path> = vec![parse_quote!(Clone), parse_quote!(Copy)];
+    for attribute in ast.attrs.iter().filter(|attribute| attribute.path().is_ident("derive")) {
+        let paths = attribute.parse_args_with(Punctuated::<syn::Path, Token![,]>::parse_terminated)?;
+        derives.extend(paths.into_iter().filter(|path| {
+            !path.segments.last().is_some_and(|segment| segment.ident == "Clone" || segment.ident == "Copy")
+        }));
+    }
+
+    let mut children = vec![];
+    for nested in &nested {
+        let directory = format!("{prefix}{}/", nested.directory);
+        let child = nested.ident;
+        let config = EnumConfig {
+            location: config.location.join(&nested.directory),
+            ..config.clone()
+        };
+        let module = format_ident!("{}", child.to_string().to_snake_case());
+        let child_variants = declare_variants(&config.location, &module, nested.ident)?;
+
+        let doc = format!("The files in `{directory}`.");
+        let child_ast: DeriveInput = parse_quote! {
+            #[doc = #doc]
+            #[derive(#(#derives),*)]
+            pub enum #child {
+                #(#child_variants),*
+            }
+        };
+
+        children.push(child_ast.to_token_stream());
+        let (child, child_names) = write_level(&child_ast, &config, Some(tree), &directory)?;
+        children.push(child);
+        accepted.extend(child_names);
+    }
+    let modules = if children.is_empty() {
+        TokenStream::new()
+    } else {
+        let vis = &ast.vis;
+        let module = format_ident!("{}", target.to_string().to_snake_case());
+
+        quote! {
+            #[doc = concat!("The enums of the subdirectories of ", stringify!(#target), ".")]
+            #vis mod #module {
+                #[allow(unused_imports)]
+                use super::*;
+
+                #(#children)*
+            }
+        }
+    };
+
+    // The rows and the schema are read with `fs`, so rebuild whenever the
+    // CSV file or, for the root, the schema changes
+    let schema = config.schema.as_ref().filter(|_| is_root).map(|(schema, _)| schema);
+    let tracked_files = config
+        .csv
+        .as_ref()
+        .map(|(csv, _)| csv)
+        .into_iter()
+        .chain(schema)
+        .map(|path| {
+            let path = path.to_string_lossy().into_owned();
+            quote!(const _: &[u8] = include_bytes!(#path);)
+        });
+
+    let serde = if cfg!(feature = "serde") {
+        write_serde_impl(ast, &accepted)
+    } else {
+        TokenStream::new()
+    };
+
+    let tokens = quote! {
+        #(#structs)*
+
+        #locale_declaration
+
+        #(#tracked_files)*
+
+        impl #impl_generics #target #type_generics #where_clause {
+            /// Every variant in declaration order, with those holding a
+            /// nested enum once for each of its variants.
+            #vis const ALL: &'static [Self] = #all;
+
+            /// The number of variants, counting those holding a nested enum
+            /// once for each of its variants.
+            #vis const COUNT: usize = #count #(+ #nested_types::COUNT)*;
+
+            /// Iterate over every variant in declaration order.
+            #vis fn iter() -> ::core::slice::Iter<'static, Self> {
+                Self::ALL.iter()
+            }
+
+            /// The name `TryFrom<&str>` accepts for this variant: the
+            /// snake_case file stem, prefixed with the subdirectory for
+            /// nested enums, or the key cell of its CSV row.
+            #vis const fn name(&self) -> &'static str {
+                match self {
+                    #(Self::#variant_idents => #names,)*
+                    #(Self::#nested_idents(inner) => inner.name(),)*
+                }
+            }
+
+            /// Path of the file backing this variant relative to the asset
+            /// location, or the CSV file and line of its row.
+            #vis const fn path(&self #locale_parameter) -> &'static str {
+                match #scrutinee {
+                    #(#patterns => #paths),*
+                }
+            }
+
+            #(#fragments)*
+        }
+
+        impl #impl_generics ::core::convert::TryFrom<&str> for #target #type_generics #where_clause {
+            type Error = wheatley::ParseAssetError;
+
+            fn try_from(name: &str) -> ::core::result::Result<Self, Self::Error> {
+                #(
+                    if let ::core::result::Result::Ok(inner) = #nested_types::try_from(name) {
+                        return ::core::result::Result::Ok(Self::#nested_idents(inner));
+                    }
+                )*
+
+                match name {
+                    #(#names => ::core::result::Result::Ok(Self::#variant_idents),)*
+                    #(#aliases)*
+                    _ => ::core::result::Result::Err(wheatley::ParseAssetError::new(stringify!(#target))),
+                }
+            }
+        }
+
+        impl #impl_generics ::core::str::FromStr for #target #type_generics #where_clause {
+            type Err = wheatley::ParseAssetError;
+
+            fn from_str(name: &str) -> ::core::result::Result<Self, Self::Err> {
+                Self::try_from(name)
+            }
+        }
+
+        impl #impl_generics ::core::fmt::Display for #target #type_generics #where_clause {
+            fn fmt(&self, formatter: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                formatter.write_str(self.name())
+            }
+        }
+
+        #serde
+
+        #modules
+    };
+
+    Ok((tokens, accepted))
+}
+
+/// Read what the enums of the tree rooted at `ast` share from every file in
+/// it, down to those in the subdirectories of nested variants.
+fn read_tree(
+    ast: &DeriveInput,
+    config: &EnumConfig,
+    variants: &[Variant],
+    translations: &BTreeMap<&str, Vec<Option<&Asset>>>,
+    nested: &[Nested],
+) -> syn::Result<Tree> {
+    let target = &ast.ident;
+
+    let mut nested_assets = Vec::new();
+    for nested in nested {
+        let directory = config.location.join(&nested.directory);
+        for asset in gather_tree(config, &directory, &format!("{}/", nested.directory), target)? {
+            nested_assets.push((nested.ident, asset));
+        }
+    }
+
+    let assets = variants
+        .iter()
+        .enumerate()
+        .flat_map(|(index, variant)| {
+            let translated = translations.values().filter_map(move |assets| assets[index]);
+
+            std::iter::once(variant.asset).chain(translated).map(|asset| (variant.ident, asset))
+        })
+        .chain(nested_assets.iter().map(|(ident, asset)| (*ident, asset)))
+        .collect::<Vec<_>>();
+
+    let sources = assets
+        .iter()
+        .map(|(variant, asset)| Source {
+            variant,
+            path: &asset.path,
+            table: &asset.table,
+        })
+        .collect::<Vec<_>>();
+
+    let schema = match &config.schema {
+        Some((path, literal)) => {
+            let mut schema = Schema::load(path, literal.span())?;
+            schema.check(&sources)?;
+            schema
+        }
+        None => Schema::infer(&sources)?,
+    };
+
+    let mut placeholders = BTreeMap::new();
+    for template in &config.templates {
+        let property = template.value();
+        let Some(details) = schema.properties.get(&property) else {
+            return Err(syn::Error::new(
+                template.span(),
+                format!("No file has the template property `{property}`"),
+            ));
+        };
+
+        let files = assets.iter().map(|(_, asset)| *asset).collect::<Vec<_>>();
+        let read = read_placeholders(&property, details, template, &files)?;
+        placeholders.insert(property, read);
+    }
+
+    Ok(Tree {
+        root: target.clone(),
+        placeholders,
+        markdown: !assets.is_empty() && assets.iter().all(|(_, asset)| asset.body.is_some()),
+        schema,
+    })
+}
+
+/// The placeholders of the template held by `property`, in order of first
+/// use. Every file must use the same placeholders.
+fn read_placeholders(
+    property: &str,
+    details: &Property,
+    template: &LitStr,
+    assets: &[&Asset],
+) -> syn::Result<Vec<syn::Ident>> {
+    let error = |message: String| syn::Error::new(template.span(), message);
+
+    if details.kind != Kind::String {
+        return Err(error(format!(
+            "The template property `{property}` is {}, not a string",
+            details.kind
+        )));
+    }
+
+    let mut errors = Errors::default();
+    let mut first: Option<(Template, &str)> = None;
+    // Locales falling back share files, which are only checked once
+    let mut checked = BTreeSet::new();
+
+    for asset in assets {
+        let Some(Value::String(text)) = asset.table.get(property) else {
+            continue;
+        };
+        if !checked.insert(asset.path.as_str()) {
+            continue;
+        }
+
+        let parsed = match Template::parse(text) {
+            Ok(parsed) => parsed,
+            Err(reason) => {
+                errors.push(error(format!("`{property}` in `{}` {reason}", asset.path)));
+                continue;
+            }
+        };
+
+        match &first {
+            Some((first, first_path)) if first.placeholder_set() != parsed.placeholder_set() => {
+                let describe = |template: &Template| format!("{{{}}}", template.placeholders.join("}, {"));
+
+                errors.push(error(format!(
+                    "`{}` gives `{property}` the placeholders {}, but `{first_path}` gives it {}",
+                    asset.path,
+                    describe(&parsed),
+                    describe(first)
+                )));
+            }
+            Some(_) => {}
+            None => first = Some((parsed, &asset.path)),
+        }
+    }
+
+    errors.finish()?;
+
+    Ok(first
+        .map(|(template, _)| template.placeholders)
+        .unwrap_or_default()
+        .iter()
+        .map(|placeholder| format_ident!("{placeholder}"))
+        .collect())
+}
+
+/// The method `method`, less its visibility, filling the template held by
+/// `property` with one argument per placeholder.
+fn write_template_method(
+    property: &str,
+    details: &Property,
+    method: &syn::Ident,
+    placeholders: &[syn::Ident],
+    arms: &[Arm],
+    scrutinee: &TokenStream,
+    locale_parameter: &TokenStream,
+) -> TokenStream {
+    let patterns = arms.iter().map(|(pattern, _)| pattern);
+    let values = arms.iter().map(|(_, asset)| {
+        let Some(asset) = asset else {
+            return quote!(inner.#method(#(#placeholders),*));
+        };
+        let message = match asset.table.get(property) {
+            Some(Value::String(text)) => Template::parse(text).ok().map(|template| {
+                let format = template.format;
+                quote!(wheatley::alloc::format!(#format, #(#placeholders = #placeholders),*))
+            }),
+            _ => None,
+        };
+
+        match (details.required, message) {
+            (true, message) => quote!(#message),
+            (false, Some(message)) => quote!(::core::option::Option::Some(#message)),
+            (false, None) => quote!(::core::option::Option::None),
+        }
+    });
+
+    let return_type = if details.required {
+        quote!(wheatley::alloc::string::String)
+    } else {
+        quote!(::core::option::Option<wheatley::alloc::string::String>)
+    };
+
+    quote! {
+        fn #method(&self #locale_parameter #(, #placeholders: impl ::core::fmt::Display)*) -> #return_type {
+            match #scrutinee {
+                #(#patterns => #values),*
+            }
+        }
+    }
+}
+
+/// `body()`, and with the `markdown` feature `html()`, for enums of
+/// Markdown files.
+fn write_markdown_methods(
+    vis: &Visibility,
+    scrutinee: &TokenStream,
+    locale_parameter: &TokenStream,
+    arms: &[Arm],
+) -> TokenStream {
+    let patterns = arms.iter().map(|(pattern, _)| pattern).collect::<Vec<_>>();
+    let bodies = arms.iter().map(|(_, asset)| match asset {
+        Some(asset) => {
+            let body = asset.body.as_deref().unwrap_or_default();
+            quote!(#body)
+        }
+        None => quote!(inner.body()),
+    });
+    let html = arms
+        .iter()
+        .map(|(_, asset)| match asset {
+            Some(asset) => formats::render_markdown(asset.body.as_deref().unwrap_or_default()).map(|html| quote!(#html)),
+            None => Some(quote!(inner.html())),
+        })
+        .collect::<Option<Vec<_>>>()
+        // Without the feature no file has HTML
+        .filter(|_| cfg!(feature = "markdown"));
+    let html = if let Some(html) = html {
+        quote! {
+            /// The body of the Markdown file backing this variant rendered
+            /// to HTML.
+            #vis const fn html(&self #locale_parameter) -> &'static str {
+                match #scrutinee {
+                    #(#patterns => #html),*
+                }
+            }
+        }
+    } else {
+        TokenStream::new()
+    };
+
+    quote! {
+        /// The Markdown file backing this variant after its front matter.
+        #vis const fn body(&self #locale_parameter) -> &'static str {
+            match #scrutinee {
+                #(#patterns => #bodies),*
+            }
+        }
+
+        #html
+    }
+}
+
+/// (De)serialise variants as their names, the same strings `Display` writes
+/// and `TryFrom<&str>` reads.
+fn write_serde_impl(ast: &DeriveInput, names: &[String]) -> TokenStream {
+    let target = &ast.ident;
+    let (impl_generics, type_generics, where_clause) = ast.generics.split_for_impl();
+
+    let mut de_generics = ast.generics.clone();
+    de_generics.params.insert(0, parse_quote!('de));
+    let (de_impl_generics, _, _) = de_generics.split_for_impl();
+
+    quote! {
+        impl #impl_generics wheatley::serde::Serialize for #target #type_generics #where_clause {
+            fn serialize<S>(&self, serializer: S) -> ::core::result::Result<S::Ok, S::Error>
+            where
+                S: wheatley::serde::Serializer,
+            {
+                serializer.serialize_str(self.name())
+            }
+        }
+
+        impl #de_impl_generics wheatley::serde::Deserialize<'de> for #target #type_generics #where_clause {
+            fn deserialize<D>(deserializer: D) -> ::core::result::Result<Self, D::Error>
+            where
+                D: wheatley::serde::Deserializer<'de>,
+            {
+                // Generic over the enum, which an item inside this function
+                // cannot name along with its generics
+                struct NameVisitor<T>(::core::marker::PhantomData<T>);
+
+                impl<'de, T> wheatley::serde::de::Visitor<'de> for NameVisitor<T>
+                where
+                    T: for<'name> ::core::convert::TryFrom<&'name str>,
+                {
+                    type Value = T;
+
+                    fn expecting(&self, formatter: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                        formatter.write_str(concat!("the name of a ", stringify!(#target), " asset"))
+                    }
+
+                    fn visit_str<E>(self, name: &str) -> ::core::result::Result<T, E>
+                    where
+                        E: wheatley::serde::de::Error,
+                    {
+                        T::try_from(name).map_err(|_| E::unknown_variant(name, &[#(#names),*]))
+                    }
+                }
+
+                deserializer.deserialize_str(NameVisitor::<Self>(::core::marker::PhantomData))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn name_methods_after_properties() {
+        let renames = vec![(format_ident!("top_speed"), format_ident!("max_speed"))];
+
+        assert_eq!(method_ident("top-speed", &renames).unwrap(), "max_speed");
+        assert_eq!(method_ident("body-style", &renames).unwrap(), "body_style");
+        assert!(method_ident("self", &renames).unwrap_err().contains("rename(self = \"...\")"));
+    }
+
+    #[test]
+    fn report_templates_with_different_placeholders() {
+        let asset = |path: &str, table: &str| Asset {
+            stem: String::new(),
+            name: String::new(),
+            path: path.to_owned(),
+            contents: TokenStream::new(),
+            bytes: TokenStream::new(),
+            utf8: true,
+            body: None,
+            table: table.parse().unwrap(),
+        };
+        let welcome = asset("welcome.toml", "greeting = \"Hello {name}\"");
+        let welcome_back = asset("welcome_back.toml", "greeting = \"Hello {name}, {count} items\"");
+        let details = Property {
+            kind: Kind::String,
+            required: true,
+        };
+        let template: LitStr = parse_quote!("greeting");
+
+        let error = read_placeholders("greeting", &details, &template, &[&welcome, &welcome_back]).unwrap_err();
+
+        assert_eq!(
+            error.to_string(),
+            "`welcome_back.toml` gives `greeting` the placeholders {name}, {count}, but `welcome.toml` gives it {name}"
+        );
+    }
+}
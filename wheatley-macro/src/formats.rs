@@ -0,0 +1,357 @@
+use crate::configuration::Markdown;
+use std::path::Path;
+use toml::Table;
+#[cfg(feature = "csv")]
+use toml::Value;
+
+/// The formats variant files can be written in. Everything but TOML and
+/// Markdown sits behind a cargo feature of the same name.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Format {
+    Toml,
+    Json,
+    Yaml,
+    Ron,
+    /// Markdown whose properties come from its front matter.
+    Markdown,
+}
+
+impl Format {
+    /// Read `#[wheatley(format = "...")]`.
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "toml" => Some(Format::Toml),
+            "json" => Some(Format::Json),
+            "yaml" => Some(Format::Yaml),
+            "ron" => Some(Format::Ron),
+            "markdown" => Some(Format::Markdown),
+            _ => None,
+        }
+    }
+
+    /// The format the extension of `path` names, if any.
+    pub fn of(path: &Path) -> Option<Self> {
+        match path.extension().and_then(std::ffi::OsStr::to_str) {
+            Some("toml") => Some(Format::Toml),
+            Some("json") => Some(Format::Json),
+            Some("yaml" | "yml") => Some(Format::Yaml),
+            Some("ron") => Some(Format::Ron),
+            Some("md" | "markdown") => Some(Format::Markdown),
+            _ => None,
+        }
+    }
+
+    /// Parse `contents` into the table the schema and code generation work
+    /// with. Values without a TOML equivalent, such as `null`, are errors.
+    pub fn read(self, contents: &str) -> Result<Table, String> {
+        match self {
+            Format::Toml => contents.parse::<Table>().map_err(|e| e.to_string()),
+            Format::Json => read_json(contents),
+            Format::Yaml => read_yaml(contents),
+            Format::Ron => read_ron(contents),
+            Format::Markdown => split_front_matter(contents).map(|(table, _)| table),
+        }
+    }
+}
+
+/// Split Markdown into its front matter and body. Front matter is TOML
+/// fenced by `+++` lines or YAML fenced by `---` lines at the very start.
+/// Markdown without front matter is all body.
+pub fn split_front_matter(contents: &str) -> Result<(Table, &str), String> {
+    let mut lines = contents.split_inclusive('\n');
+    let first_line = lines.next().unwrap_or_default();
+
+    let format = match first_line.trim_end() {
+        "+++" => Format::Toml,
+        "---" => Format::Yaml,
+        _ => return Ok((Table::new(), contents)),
+    };
+    let fence = first_line.trim_end();
+
+    let start = first_line.len();
+    let mut end = start;
+    for line in lines {
+        if line.trim_end() == fence {
+            let front_matter = &contents[start..end];
+            let body = &contents[end + line.len()..];
+
+            return Ok((format.read(front_matter)?, body));
+        }
+        end += line.len();
+    }
+
+    Err(format!("The front matter opened by `{fence}` is never closed"))
+}
+
+/// Replace the contents of the Markdown files among `assets` with their
+/// body, or its HTML.
+pub fn strip_front_matter(assets: &mut [(String, Vec<u8>)], markdown: Markdown) -> Result<(), String> {
+    for (key, contents) in assets.iter_mut() {
+        if Format::of(Path::new(key)) != Some(Format::Markdown) {
+            continue;
+        }
+
+        let text = std::str::from_utf8(contents).map_err(|e| format!("Cannot read `{key}`: {e}"))?;
+        let (_, body) = split_front_matter(text).map_err(|reason| format!("Cannot read `{key}`: {reason}"))?;
+
+        *contents = match markdown {
+            Markdown::Body => body.as_bytes().to_vec(),
+            Markdown::Html => render_markdown(body)
+                .ok_or_else(|| String::from("Enable the `markdown` feature of wheatley to render Markdown"))?
+                .into_bytes(),
+        };
+    }
+
+    Ok(())
+}
+
+/// Render the Markdown `body` to HTML, if the `markdown` feature is on.
+#[cfg(feature = "markdown")]
+pub fn render_markdown(body: &str) -> Option<String> {
+    let mut html = String::new();
+    pulldown_cmark::html::push_html(&mut html, pulldown_cmark::Parser::new(body));
+
+    Some(html)
+}
+
+#[cfg(not(feature = "markdown"))]
+pub fn render_markdown(_: &str) -> Option<String> {
+    None
+}
+
+#[cfg(feature = "json")]
+fn read_json(contents: &str) -> Result<Table, String> {
+    serde_json::from_str(contents).map_err(|e| e.to_string())
+}
+
+#[cfg(not(feature = "json"))]
+fn read_json(_: &str) -> Result<Table, String> {
+    Err(String::from("Enable the `json` feature of wheatley to read JSON files"))
+}
+
+#[cfg(feature = "yaml")]
+fn read_yaml(contents: &str) -> Result<Table, String> {
+    serde_yaml::from_str(contents).map_err(|e| e.to_string())
+}
+
+#[cfg(not(feature = "yaml"))]
+fn read_yaml(_: &str) -> Result<Table, String> {
+    Err(String::from("Enable the `yaml` feature of wheatley to read YAML files"))
+}
+
+#[cfg(feature = "ron")]
+fn read_ron(contents: &str) -> Result<Table, String> {
+    // Going through `ron::Value` accepts both `(key: ..)` structs and
+    // `{"key": ..}` maps
+    ron::from_str::<ron::Value>(contents)
+        .map_err(|e| e.to_string())?
+        .into_rust()
+        .map_err(|e| e.to_string())
+}
+
+#[cfg(not(feature = "ron"))]
+fn read_ron(_: &str) -> Result<Table, String> {
+    Err(String::from("Enable the `ron` feature of wheatley to read RON files"))
+}
+
+/// A row of a CSV file backing one variant.
+pub struct Row {
+    /// The cell of the key column.
+    pub key: String,
+    pub line: u64,
+    /// The row exactly as it is in the file.
+    pub text: String,
+    /// Every other non-empty cell, typed per column.
+    pub table: Table,
+}
+
+/// Read the rows of a CSV file with a header. Each column takes the
+/// narrowest type all of its non-empty cells parse as: integer, float,
+/// boolean, datetime or, failing those, string. Rows with a different
+/// number of cells than the header are errors.
+#[cfg(feature = "csv")]
+pub fn read_csv(contents: &str, key: &str) -> Result<Vec<Row>, String> {
+    let mut reader = csv::Reader::from_reader(contents.as_bytes());
+
+    let headers = reader.headers().map_err(|e| e.to_string())?.clone();
+    let key_column = headers
+        .iter()
+        .position(|header| header == key)
+        .ok_or_else(|| format!("There is no `{key}` column, the columns are: {}", headers.iter().collect::<Vec<_>>().join(", ")))?;
+
+    let records = reader
+        .records()
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+
+    let parsers = (0..headers.len())
+        .map(|column| column_parser(records.iter().map(|record| &record[column])))
+        .collect::<Vec<_>>();
+
+    let rows = records
+        .iter()
+        .enumerate()
+        .map(|(index, record)| {
+            let position = record.position().unwrap();
+            let end = records
+                .get(index + 1)
+                .and_then(|next| next.position())
+                .map_or(contents.len(), |next| next.byte() as usize);
+            let text = contents[position.byte() as usize..end].trim_end_matches(['\r', '\n']);
+
+            let table = record
+                .iter()
+                .enumerate()
+                .filter(|(column, cell)| *column != key_column && !cell.is_empty())
+                .map(|(column, cell)| (headers[column].to_owned(), parsers[column](cell)))
+                .collect();
+
+            Row {
+                key: record[key_column].to_owned(),
+                line: position.line(),
+                text: text.to_owned(),
+                table,
+            }
+        })
+        .collect();
+
+    Ok(rows)
+}
+
+#[cfg(not(feature = "csv"))]
+pub fn read_csv(_: &str, _: &str) -> Result<Vec<Row>, String> {
+    Err(String::from("Enable the `csv` feature of wheatley to read CSV files"))
+}
+
+#[cfg(feature = "csv")]
+fn column_parser<'a>(cells: impl Iterator<Item = &'a str> + Clone) -> fn(&str) -> Value {
+    let mut cells = cells.filter(|cell| !cell.is_empty());
+
+    if cells.clone().all(|cell| cell.parse::<i64>().is_ok()) {
+        |cell| Value::Integer(cell.parse().unwrap())
+    } else if cells.clone().all(|cell| cell.parse::<f64>().is_ok()) {
+        |cell| Value::Float(cell.parse().unwrap())
+    } else if cells.clone().all(|cell| cell.parse::<bool>().is_ok()) {
+        |cell| Value::Boolean(cell.parse().unwrap())
+    } else if cells.all(|cell| cell.parse::<toml::value::Datetime>().is_ok()) {
+        |cell| Value::Datetime(cell.parse().unwrap())
+    } else {
+        |cell| Value::String(cell.to_owned())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    #[test_case("civic.json", Some(Format::Json))]
+    #[test_case("civic.yml", Some(Format::Yaml))]
+    #[test_case("civic.ron", Some(Format::Ron))]
+    #[test_case("civic.toml", Some(Format::Toml))]
+    #[test_case("civic.md", Some(Format::Markdown))]
+    #[test_case("civic.txt", None)]
+    #[test_case("civic", None)]
+    fn pick_format_by_extension(path: &str, expected: Option<Format>) {
+        assert_eq!(Format::of(Path::new(path)), expected);
+    }
+
+    #[test]
+    fn split_toml_front_matter() {
+        let (table, body) = split_front_matter("+++\ntitle = \"Hello\"\n+++\n# Hello\n").unwrap();
+
+        assert_eq!(table["title"].as_str(), Some("Hello"));
+        assert_eq!(body, "# Hello\n");
+    }
+
+    #[test]
+    fn markdown_without_front_matter_is_all_body() {
+        let (table, body) = split_front_matter("# Hello\n---\n").unwrap();
+
+        assert!(table.is_empty());
+        assert_eq!(body, "# Hello\n---\n");
+    }
+
+    #[test]
+    fn reject_unclosed_front_matter() {
+        assert!(split_front_matter("+++\ntitle = \"Hello\"\n# Hello\n").is_err());
+    }
+
+    #[cfg(feature = "yaml")]
+    #[test]
+    fn split_yaml_front_matter() {
+        let (table, body) = split_front_matter("---\r\ntitle: Hello\r\n---\r\nBody").unwrap();
+
+        assert_eq!(table["title"].as_str(), Some("Hello"));
+        assert_eq!(body, "Body");
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn read_json() {
+        let table = Format::Json
+            .read(r#"{ "make": "Honda", "engine": { "cylinders": 4 } }"#)
+            .unwrap();
+
+        assert_eq!(table, "make = 'Honda'\nengine = { cylinders = 4 }".parse::<Table>().unwrap());
+    }
+
+    #[cfg(feature = "yaml")]
+    #[test]
+    fn read_yaml() {
+        let table = Format::Yaml.read("make: Honda\ncolours: [red, blue]\n").unwrap();
+
+        assert_eq!(table, "make = 'Honda'\ncolours = ['red', 'blue']".parse::<Table>().unwrap());
+    }
+
+    #[cfg(feature = "ron")]
+    #[test]
+    fn read_ron() {
+        let table = Format::Ron
+            .read(r#"(make: "Honda", year: 2024, engine: (cylinders: 4))"#)
+            .unwrap();
+
+        assert_eq!(
+            table,
+            "make = 'Honda'\nyear = 2024\nengine = { cylinders = 4 }".parse::<Table>().unwrap()
+        );
+    }
+
+    #[cfg(feature = "csv")]
+    #[test]
+    fn read_typed_csv_columns() {
+        let rows = read_csv(
+            "title,published,rating,pen_name\nWacky Wednesday,1974,4.5,Theo LeSieg\nGrumpy Monkey,2018,4,\n",
+            "title",
+        )
+        .unwrap();
+
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[1].key, "Grumpy Monkey");
+        assert_eq!(rows[1].line, 3);
+        assert_eq!(rows[1].text, "Grumpy Monkey,2018,4,");
+        assert_eq!(
+            rows[0].table,
+            "published = 1974\nrating = 4.5\npen_name = 'Theo LeSieg'".parse::<Table>().unwrap()
+        );
+        assert_eq!(
+            rows[1].table,
+            "published = 2018\nrating = 4.0".parse::<Table>().unwrap()
+        );
+    }
+
+    #[cfg(feature = "csv")]
+    #[test]
+    fn reject_rows_with_wrong_column_count() {
+        assert!(read_csv("title,published\nWacky Wednesday,1974,extra\n", "title").is_err());
+        assert!(read_csv("title,published\nWacky Wednesday\n", "title").is_err());
+    }
+
+    #[cfg(feature = "csv")]
+    #[test]
+    fn reject_missing_key_column() {
+        let error = read_csv("name,published\n", "title").err().unwrap();
+
+        assert!(error.contains("name, published"));
+    }
+}
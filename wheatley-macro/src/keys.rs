@@ -0,0 +1,165 @@
+use proc_macro2::{Ident, TokenStream};
+use quote::quote;
+use std::collections::HashMap;
+use std::fmt::{Debug, Display};
+use std::hash::Hash;
+use std::str::FromStr;
+use syn::LitStr;
+use wheatley_build::{KeyPattern, Layout};
+
+/// `key: u32 from "sprite_{id}.png"`: look files up by an integer parsed
+/// out of their path instead of the path itself.
+#[derive(Debug)]
+pub struct KeyConfig {
+    pub ty: Ident,
+    pub pattern: LitStr,
+}
+
+impl KeyConfig {
+    pub fn ty(&self) -> TokenStream {
+        let ty = &self.ty;
+
+        quote!(#ty)
+    }
+
+    /// Key the assets, whose paths start with `prefix`, by what the pattern
+    /// captures of the rest of their path.
+    pub fn lay_out(&self, assets: Vec<(String, Vec<u8>)>, prefix: &str) -> syn::Result<Layout> {
+        let pattern = KeyPattern::parse(&self.pattern.value())
+            .map_err(|message| syn::Error::new(self.pattern.span(), message))?;
+
+        match self.ty.to_string().as_str() {
+            "u8" => self.keyed::<u8>(&pattern, assets, prefix),
+            "u16" => self.keyed::<u16>(&pattern, assets, prefix),
+            "u32" => self.keyed::<u32>(&pattern, assets, prefix),
+            "u64" => self.keyed::<u64>(&pattern, assets, prefix),
+            "i8" => self.keyed::<i8>(&pattern, assets, prefix),
+            "i16" => self.keyed::<i16>(&pattern, assets, prefix),
+            "i32" => self.keyed::<i32>(&pattern, assets, prefix),
+            "i64" => self.keyed::<i64>(&pattern, assets, prefix),
+            other => Err(syn::Error::new(
+                self.ty.span(),
+                format!("Unsupported key type `{other}`, expected one of u8, u16, u32, u64, i8, i16, i32 or i64"),
+            )),
+        }
+    }
+
+    fn keyed<K>(
+        &self,
+        pattern: &KeyPattern,
+        mut assets: Vec<(String, Vec<u8>)>,
+        prefix: &str,
+    ) -> syn::Result<Layout>
+    where
+        K: FromStr + Hash + Debug + Display + Clone + Eq,
+    {
+        let error = |message: String| syn::Error::new(self.pattern.span(), message);
+
+        // Report problems in a stable order
+        assets.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+        let mut owners = HashMap::new();
+        let mut keyed_assets = Vec::with_capacity(assets.len());
+
+        for (path, contents) in assets {
+            let unprefixed = path.strip_prefix(prefix).unwrap_or(&path);
+            let capture = pattern.capture(unprefixed).ok_or_else(|| {
+                error(format!(
+                    "`{path}` does not match the key pattern `{}`",
+                    self.pattern.value()
+                ))
+            })?;
+
+            let key = capture.parse::<K>().map_err(|_| {
+                error(format!(
+                    "Cannot parse `{capture}` of `{path}` as a {} for `{{{}}}`",
+                    self.ty,
+                    pattern.placeholder()
+                ))
+            })?;
+
+            if let Some(owner) = owners.insert(key.clone(), path.clone()) {
+                return Err(error(format!("`{owner}` and `{path}` both have the key {key}")));
+            }
+
+            keyed_assets.push((key, path, contents));
+        }
+
+        Ok(Layout::from_keyed_assets(keyed_assets))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proc_macro2::Span;
+
+    fn key_config(ty: &str, pattern: &str) -> KeyConfig {
+        KeyConfig {
+            ty: Ident::new(ty, Span::call_site()),
+            pattern: LitStr::new(pattern, Span::call_site()),
+        }
+    }
+
+    fn assets(paths: &[&str]) -> Vec<(String, Vec<u8>)> {
+        paths
+            .iter()
+            .map(|path| ((*path).to_owned(), path.as_bytes().to_vec()))
+            .collect()
+    }
+
+    #[test]
+    fn key_files_by_parsed_id() {
+        let layout = key_config("u32", "sprite_{id}.png")
+            .lay_out(assets(&["sprite_1.png", "sprite_20.png"]), "")
+            .unwrap();
+
+        assert_eq!(layout.fingerprints.len(), 2);
+        assert_eq!(layout.keys.len(), "sprite_1.pngsprite_20.png".len());
+    }
+
+    #[test]
+    fn match_pattern_after_prefix() {
+        let layout = key_config("u32", "sprite_{id}.png")
+            .lay_out(assets(&["/sprites/sprite_1.png", "/sprites/sprite_20.png"]), "/sprites/")
+            .unwrap();
+
+        assert_eq!(layout.paths().count(), 2);
+    }
+
+    #[test]
+    fn reject_unparsable_id() {
+        let error = key_config("u8", "sprite_{id}.png")
+            .lay_out(assets(&["sprite_1.png", "sprite_300.png"]), "")
+            .unwrap_err();
+
+        assert!(error.to_string().contains("sprite_300.png"));
+    }
+
+    #[test]
+    fn reject_name_outside_pattern() {
+        let error = key_config("u32", "sprite_{id}.png")
+            .lay_out(assets(&["sprite_1.png", "readme.md"]), "")
+            .unwrap_err();
+
+        assert!(error.to_string().contains("readme.md"));
+    }
+
+    #[test]
+    fn reject_duplicate_id() {
+        let error = key_config("u32", "sprite_{id}.png")
+            .lay_out(assets(&["sprite_1.png", "sprite_01.png"]), "")
+            .unwrap_err();
+
+        assert!(error.to_string().contains("both have the key 1"));
+    }
+
+    #[test]
+    fn reject_unsupported_key_type() {
+        let error = key_config("usize", "sprite_{id}.png")
+            .lay_out(assets(&["sprite_1.png"]), "")
+            .unwrap_err();
+
+        assert!(error.to_string().contains("usize"));
+    }
+}
@@ -0,0 +1,178 @@
+use heck::ToUpperCamelCase;
+use proc_macro2::TokenStream;
+use quote::quote;
+use std::fs;
+use std::path::Path;
+use syn::{Ident, LitStr, Visibility};
+
+/// The locales of a localised enum, one per subdirectory of its location.
+pub struct Locales {
+    /// Locale codes such as `en` or `pt-BR`, sorted, with their variant in
+    /// the generated locale enum.
+    pub codes: Vec<(String, Ident)>,
+    /// The locale every other one falls back to in the end.
+    pub default: String,
+}
+
+impl Locales {
+    /// Read the locales from the subdirectories of `location`. `default`
+    /// must be one of them.
+    pub fn discover(location: &Path, default: &LitStr) -> syn::Result<Self> {
+        let error = |message: String| syn::Error::new(default.span(), message);
+
+        let entries = fs::read_dir(location).map_err(|e| error(format!("Cannot read {}: {e}", location.display())))?;
+
+        let mut codes = Vec::new();
+        for entry in entries {
+            let entry = entry.map_err(|e| error(e.to_string()))?;
+            if !entry.path().is_dir() {
+                continue;
+            }
+
+            let code = entry.file_name().to_string_lossy().into_owned();
+            let ident = syn::parse_str::<Ident>(&code.to_upper_camel_case())
+                .map_err(|_| error(format!("`{code}` in {} is not a locale code", location.display())))?;
+
+            codes.push((code, ident));
+        }
+        codes.sort();
+
+        let default = default.value();
+        if !codes.iter().any(|(code, _)| *code == default) {
+            return Err(error(format!(
+                "There is no `{default}` directory in {} for the default locale",
+                location.display()
+            )));
+        }
+
+        Ok(Self { codes, default })
+    }
+
+    /// The locales `code` reads from, nearest first: itself, each shorter
+    /// locale it extends such as `pt` for `pt-BR`, then the default.
+    pub fn chain<'a>(&'a self, code: &'a str) -> Vec<&'a str> {
+        let mut chain = vec![code];
+
+        let mut rest = code;
+        while let Some(index) = rest.rfind(['-', '_']) {
+            rest = &rest[..index];
+            if self.codes.iter().any(|(known, _)| known == rest) {
+                chain.push(rest);
+            }
+        }
+
+        if code != self.default {
+            chain.push(&self.default);
+        }
+
+        chain
+    }
+
+    fn ident(&self, code: &str) -> &Ident {
+        &self.codes.iter().find(|(known, _)| known == code).unwrap().1
+    }
+
+    /// The enum listing the locales, e.g. `ErrorsLocale` for `enum Errors`.
+    pub fn write_enum(&self, name: &Ident, vis: &Visibility) -> TokenStream {
+        let (codes, idents): (Vec<_>, Vec<_>) = self.codes.iter().map(|(code, ident)| (code, ident)).unzip();
+        let default = self.ident(&self.default);
+        let fallbacks = self.codes.iter().map(|(code, _)| match self.chain(code).get(1) {
+            Some(fallback) => {
+                let fallback = self.ident(fallback);
+                quote!(::core::option::Option::Some(Self::#fallback))
+            }
+            None => quote!(::core::option::Option::None),
+        });
+
+        quote! {
+            #[doc = concat!("The locales of ", stringify!(#name), ".")]
+            #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+            #vis enum #name {
+                #(#idents),*
+            }
+
+            impl #name {
+                /// Every locale, sorted by code.
+                pub const ALL: &'static [Self] = &[#(Self::#idents),*];
+
+                /// The name of the directory holding this locale.
+                pub const fn code(&self) -> &'static str {
+                    match self {
+                        #(Self::#idents => #codes),*
+                    }
+                }
+
+                /// The locale whose files this one falls back to where it
+                /// has none.
+                pub const fn fallback(&self) -> ::core::option::Option<Self> {
+                    match self {
+                        #(Self::#idents => #fallbacks),*
+                    }
+                }
+            }
+
+            impl ::core::default::Default for #name {
+                fn default() -> Self {
+                    Self::#default
+                }
+            }
+
+            impl ::core::convert::TryFrom<&str> for #name {
+                type Error = wheatley::ParseAssetError;
+
+                fn try_from(code: &str) -> ::core::result::Result<Self, Self::Error> {
+                    match code {
+                        #(#codes => ::core::result::Result::Ok(Self::#idents),)*
+                        _ => ::core::result::Result::Err(wheatley::ParseAssetError::new(stringify!(#name))),
+                    }
+                }
+            }
+
+            impl ::core::str::FromStr for #name {
+                type Err = wheatley::ParseAssetError;
+
+                fn from_str(code: &str) -> ::core::result::Result<Self, Self::Err> {
+                    Self::try_from(code)
+                }
+            }
+
+            impl ::core::fmt::Display for #name {
+                fn fmt(&self, formatter: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                    formatter.write_str(self.code())
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use quote::format_ident;
+
+    fn locales(codes: &[&str], default: &str) -> Locales {
+        Locales {
+            codes: codes
+                .iter()
+                .map(|code| (code.to_string(), format_ident!("{}", code.to_upper_camel_case())))
+                .collect(),
+            default: default.to_owned(),
+        }
+    }
+
+    #[test]
+    fn fall_back_through_shorter_locales() {
+        let locales = locales(&["en", "pt", "pt-BR"], "en");
+
+        assert_eq!(locales.chain("pt-BR"), ["pt-BR", "pt", "en"]);
+        assert_eq!(locales.chain("pt"), ["pt", "en"]);
+        assert_eq!(locales.chain("en"), ["en"]);
+    }
+
+    #[test]
+    fn skip_missing_shorter_locales() {
+        let locales = locales(&["en", "zh_Hant_TW"], "en");
+
+        assert_eq!(locales.chain("zh_Hant_TW"), ["zh_Hant_TW", "en"]);
+    }
+}
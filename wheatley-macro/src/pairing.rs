@@ -0,0 +1,838 @@
+use crate::enum_impl::EnumConfig;
+use crate::formats::{self, Format};
+use crate::locales::Locales;
+use crate::schema::Errors;
+use heck::{ToSnakeCase, ToUpperCamelCase};
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::Path;
+use syn::{parse_quote, DeriveInput, LitStr};
+use toml::Table;
+
+/// Options given through `#[wheatley(...)]` on a variant.
+#[derive(Default)]
+struct VariantConfig {
+    /// The file backing the variant, overriding the one its name implies.
+    file: Option<LitStr>,
+    /// Further names `TryFrom<&str>` accepts.
+    aliases: Vec<LitStr>,
+}
+
+impl VariantConfig {
+    fn from_attributes(variant: &syn::Variant) -> syn::Result<Self> {
+        let mut config = Self::default();
+
+        for attribute in variant.attrs.iter().filter(|a| a.path().is_ident("wheatley")) {
+            attribute.parse_nested_meta(|meta| {
+                if meta.path.is_ident("file") {
+                    config.file = Some(meta.value()?.parse::<LitStr>()?);
+                    Ok(())
+                } else if meta.path.is_ident("alias") {
+                    config.aliases.push(meta.value()?.parse::<LitStr>()?);
+                    Ok(())
+                } else {
+                    Err(meta.error("Unknown wheatley variant option"))
+                }
+            })?;
+        }
+
+        Ok(config)
+    }
+}
+
+/// A file, or a CSV row, backing one variant.
+pub struct Asset {
+    /// The snake_case name of the variant the asset backs by default.
+    pub stem: String,
+    /// What `TryFrom<&str>` accepts: the file stem or the key cell.
+    pub name: String,
+    /// Where the asset is, for `path()` and error messages.
+    pub path: String,
+    /// An expression for `contents()`, if `utf8`.
+    pub contents: TokenStream,
+    /// An expression for `bytes()`.
+    pub bytes: TokenStream,
+    pub utf8: bool,
+    /// What follows the front matter of a Markdown file.
+    pub body: Option<String>,
+    pub table: Table,
+}
+
+/// A variant and the file, or CSV row, backing it.
+pub struct Variant<'a> {
+    pub ident: &'a syn::Ident,
+    pub asset: &'a Asset,
+    /// What `TryFrom<&str>` accepts besides the aliases.
+    pub name: String,
+    pub aliases: Vec<LitStr>,
+}
+
+/// A variant backed by a subdirectory, holding the enum generated for it.
+pub struct Nested<'a> {
+    pub ident: &'a syn::Ident,
+    /// The enum it holds, e.g. `books::DeborahDiesen`.
+    pub ty: &'a syn::Type,
+    /// The name of the subdirectory within the location.
+    pub directory: String,
+}
+
+/// Match every variant with the file of the same snake_case stem, or the
+/// file it names, and every variant holding one field with the subdirectory
+/// of the same snake_case name. All unbacked variants, clashing names and,
+/// unless allowed, all files without a variant are reported at once.
+pub fn pair_variants<'a>(
+    ast: &'a DeriveInput,
+    target_enum: &'a syn::DataEnum,
+    asset_register: &'a BTreeMap<String, Asset>,
+    config: &EnumConfig,
+) -> syn::Result<(Vec<Variant<'a>>, Vec<Nested<'a>>)> {
+    let mut errors = Errors::default();
+    let mut variants = Vec::new();
+    let mut nested = Vec::new();
+
+    for variant in target_enum.variants.iter() {
+        match &variant.fields {
+            syn::Fields::Unit => {}
+            syn::Fields::Unnamed(fields) if fields.unnamed.len() == 1 => {
+                match pair_directory(ast, variant, &fields.unnamed[0].ty, config) {
+                    Ok(directory) => nested.push(directory),
+                    Err(error) => errors.push(error),
+                }
+                continue;
+            }
+            fields => {
+                errors.push(syn::Error::new_spanned(
+                    fields,
+                    "Variants are backed by a file and hold nothing, or by a subdirectory and hold its enum",
+                ));
+                continue;
+            }
+        }
+
+        let options = match VariantConfig::from_attributes(variant) {
+            Ok(options) => options,
+            Err(error) => {
+                errors.push(error);
+                continue;
+            }
+        };
+
+        let paired = match &options.file {
+            Some(file) => pair_file(file, asset_register, config),
+            None => pair_stem(&variant.ident, asset_register, config),
+        };
+
+        match paired {
+            Ok(asset) => {
+                // A file named explicitly is parsed by its stem as written
+                let name = match &options.file {
+                    Some(file) => Path::new(&file.value())
+                        .file_stem()
+                        .map(|stem| stem.to_string_lossy().into_owned())
+                        .unwrap_or_default(),
+                    None => asset.name.clone(),
+                };
+
+                variants.push(Variant {
+                    ident: &variant.ident,
+                    asset,
+                    name,
+                    aliases: options.aliases,
+                });
+            }
+            Err(error) => errors.push(error),
+        }
+    }
+
+    // Every name must parse into one variant only
+    let mut names: BTreeMap<String, &syn::Ident> = BTreeMap::new();
+    for variant in &variants {
+        if let Some(other) = names.insert(variant.name.clone(), variant.ident) {
+            errors.push(syn::Error::new_spanned(
+                variant.ident,
+                format!("`{}` names both `{other}` and `{}`", variant.name, variant.ident),
+            ));
+        }
+    }
+    for variant in &variants {
+        for alias in &variant.aliases {
+            if let Some(other) = names.insert(alias.value(), variant.ident) {
+                errors.push(syn::Error::new(
+                    alias.span(),
+                    format!("`{}` names both `{other}` and `{}`", alias.value(), variant.ident),
+                ));
+            }
+        }
+    }
+
+    if !config.allow_extra_files {
+        let extra_assets = asset_register
+            .values()
+            .filter(|asset| !variants.iter().any(|variant| std::ptr::eq(variant.asset, *asset)))
+            .map(|asset| asset.path.as_str())
+            .collect::<Vec<_>>();
+
+        if !extra_assets.is_empty() {
+            let assets = if config.csv.is_some() { "rows" } else { "files" };
+
+            errors.push(syn::Error::new_spanned(
+                &ast.ident,
+                format!(
+                    "No variant matches the {assets}: {}. Add a variant for each or use #[wheatley(allow_extra_files)]",
+                    extra_assets.join(", ")
+                ),
+            ));
+        }
+    }
+
+    errors.finish().map(|()| (variants, nested))
+}
+
+/// Find the subdirectory of the same snake_case name as `variant`, whose
+/// enum the variant holds from the module named after the derived enum.
+fn pair_directory<'a>(
+    ast: &DeriveInput,
+    variant: &'a syn::Variant,
+    ty: &'a syn::Type,
+    config: &EnumConfig,
+) -> syn::Result<Nested<'a>> {
+    let ident = &variant.ident;
+
+    if config.csv.is_some() || config.default_locale.is_some() {
+        return Err(syn::Error::new_spanned(
+            &variant.fields,
+            "Only enums of a directory of files can hold the enums of its subdirectories",
+        ));
+    }
+
+    let options = VariantConfig::from_attributes(variant)?;
+    if options.file.is_some() || !options.aliases.is_empty() {
+        return Err(syn::Error::new_spanned(
+            ident,
+            "Variants holding a nested enum are named by it, so `file` and `alias` do not apply",
+        ));
+    }
+
+    let module = format_ident!("{}", ast.ident.to_string().to_snake_case());
+    let expected: syn::Type = parse_quote!(#module::#ident);
+    if *ty != expected {
+        return Err(syn::Error::new_spanned(
+            ty,
+            format!("`{ident}` holds the enum generated for its subdirectory, `{module}::{ident}`"),
+        ));
+    }
+
+    let stem = ident.to_string().to_snake_case();
+    let directory = subdirectories(&config.location)
+        .into_iter()
+        .find(|directory| directory.to_upper_camel_case().to_snake_case() == stem)
+        .ok_or_else(|| {
+            syn::Error::new_spanned(
+                ident,
+                format!(
+                    "No subdirectory holding files backs variant `{ident}`, expected one named `{stem}` in {}",
+                    config.location.display()
+                ),
+            )
+        })?;
+
+    Ok(Nested { ident, ty, directory })
+}
+
+/// Find the file named by `#[wheatley(file = "...")]`.
+fn pair_file<'a>(file: &LitStr, asset_register: &'a BTreeMap<String, Asset>, config: &EnumConfig) -> syn::Result<&'a Asset> {
+    if config.csv.is_some() {
+        return Err(syn::Error::new(
+            file.span(),
+            "Variants of a CSV backed enum are matched by their key cell, not a file",
+        ));
+    }
+
+    asset_register.get(&file.value()).ok_or_else(|| {
+        syn::Error::new(
+            file.span(),
+            format!("No file named `{}` in {}", file.value(), config.location.display()),
+        )
+    })
+}
+
+/// Find the only file, or row, whose stem is the snake_case name of `variant`.
+fn pair_stem<'a>(
+    variant: &syn::Ident,
+    asset_register: &'a BTreeMap<String, Asset>,
+    config: &EnumConfig,
+) -> syn::Result<&'a Asset> {
+    let stem = variant.to_string().to_snake_case();
+    let mut candidates = asset_register.values().filter(|asset| asset.stem == stem);
+
+    match (candidates.next(), candidates.next()) {
+        (Some(asset), None) => Ok(asset),
+        (Some(asset), Some(other)) => Err(syn::Error::new_spanned(
+            variant,
+            format!(
+                "`{}` and `{}` both back `{variant}`, pick one with #[wheatley(file = \"...\")]",
+                asset.path, other.path
+            ),
+        )),
+        (None, _) => Err(syn::Error::new_spanned(
+            variant,
+            match &config.csv {
+                Some((csv, key)) => format!(
+                    "No row backs variant `{variant}`, expected a row whose `{key}` is `{stem}` in snake_case in {}",
+                    csv.display()
+                ),
+                None => format!(
+                    "No file backs variant `{variant}`, expected a file named `{stem}` in {}",
+                    config.location.display()
+                ),
+            },
+        )),
+    }
+}
+
+/// Map the name of every file in `directory`, the location or a directory
+/// within it, to its contents. Paths start with `prefix`, the directory
+/// relative to the location. Unreadable files are reported against `target`.
+pub fn gather_assets(
+    config: &EnumConfig,
+    directory: &Path,
+    prefix: &str,
+    target: &syn::Ident,
+) -> syn::Result<BTreeMap<String, Asset>> {
+    let error = |message: String| syn::Error::new_spanned(target, message);
+    let mut errors = Errors::default();
+    let mut asset_register: BTreeMap<String, Asset> = BTreeMap::new();
+
+    let entries = fs::read_dir(directory).map_err(|e| error(format!("Cannot read {}: {e}", directory.display())))?;
+
+    // TODO: During development read the file from disk
+    // while building in production read embed the data
+    for entry in entries {
+        let entry = entry.map_err(|e| error(e.to_string()))?;
+        if entry.path().is_dir() {
+            continue;
+        }
+
+        let file_name = entry.file_name().to_string_lossy().into_owned();
+        let path = format!("{prefix}{file_name}");
+
+        // Broken symlinks cannot be canonicalized, and names that are not
+        // UTF-8 cannot name a variant. A symlink names its variant itself,
+        // whatever it points to.
+        let full_path = match entry.path().canonicalize() {
+            Ok(full_path) => full_path,
+            Err(e) => {
+                errors.push(error(format!("Cannot read `{path}`: {e}")));
+                continue;
+            }
+        };
+        let entry_path = entry.path();
+        let Some(file_stem) = entry_path.file_stem().and_then(std::ffi::OsStr::to_str) else {
+            errors.push(error(format!("Cannot read `{path}`: its name is not UTF-8")));
+            continue;
+        };
+
+        let bytes = match fs::read(&full_path) {
+            Ok(bytes) => bytes,
+            Err(e) => {
+                errors.push(error(format!("Cannot read `{path}`: {e}")));
+                continue;
+            }
+        };
+        let text = std::str::from_utf8(&bytes).map_err(|e| e.to_string());
+        let utf8 = text.is_ok();
+
+        // Raw files and those in no structured format, such as `.txt`, only
+        // have contents
+        let format = config.format.or_else(|| Format::of(&entry_path)).filter(|_| !config.raw);
+        let read = match format {
+            Some(Format::Markdown) => text.and_then(|text| {
+                formats::split_front_matter(text).map(|(table, body)| (table, Some(body.to_owned())))
+            }),
+            Some(format) => text.and_then(|text| format.read(text)).map(|table| (table, None)),
+            None if utf8 || config.raw => Ok((Table::new(), None)),
+            None => Err(String::from("it is not UTF-8, embed it with #[wheatley(raw)]")),
+        };
+
+        let (table, body) = match read {
+            Ok(read) => read,
+            Err(reason) => {
+                errors.push(error(format!("Cannot read `{path}`: {reason}")));
+                continue;
+            }
+        };
+
+        let full_path = full_path.to_string_lossy().into_owned();
+
+        asset_register.insert(
+            file_name,
+            Asset {
+                // The stem of the variant the file names, so that stems such
+                // as `sprite_1` find `Sprite1`
+                stem: file_stem.to_upper_camel_case().to_snake_case(),
+                name: file_stem.to_snake_case(),
+                path,
+                contents: quote!(include_str!(#full_path)),
+                bytes: quote!(include_bytes!(#full_path)),
+                utf8,
+                body,
+                table,
+            },
+        );
+    }
+
+    errors.finish().map(|()| asset_register)
+}
+
+/// Every file in `directory` and, at any depth, its subdirectories, with
+/// paths starting with `prefix`.
+pub fn gather_tree(config: &EnumConfig, directory: &Path, prefix: &str, target: &syn::Ident) -> syn::Result<Vec<Asset>> {
+    let mut assets = gather_assets(config, directory, prefix, target)?
+        .into_values()
+        .collect::<Vec<_>>();
+
+    for subdirectory in subdirectories(directory) {
+        assets.extend(gather_tree(
+            config,
+            &directory.join(&subdirectory),
+            &format!("{prefix}{subdirectory}/"),
+            target,
+        )?);
+    }
+
+    Ok(assets)
+}
+
+/// The subdirectories of `directory` holding files at any depth.
+pub fn subdirectories(directory: &Path) -> BTreeSet<String> {
+    fn holds_files(directory: &Path) -> bool {
+        fs::read_dir(directory)
+            .into_iter()
+            .flatten()
+            .flatten()
+            .any(|entry| !entry.path().is_dir() || holds_files(&entry.path()))
+    }
+
+    fs::read_dir(directory)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter(|entry| entry.path().is_dir() && holds_files(&entry.path()))
+        .map(|entry| entry.file_name().to_string_lossy().into_owned())
+        .collect()
+}
+
+/// The variants of an enum declared from `directory`: one per file, named
+/// after its stem, then one per subdirectory holding files, holding the enum
+/// of the same name in `module`. Entries naming no variant are reported
+/// against `target`.
+pub fn declare_variants(directory: &Path, module: &syn::Ident, target: &syn::Ident) -> syn::Result<Vec<syn::Variant>> {
+    let error = |message: String| syn::Error::new_spanned(target, message);
+    let entries = fs::read_dir(directory).map_err(|e| error(format!("Cannot read {}: {e}", directory.display())))?;
+
+    let mut errors = Errors::default();
+    let mut files = BTreeSet::new();
+
+    for entry in entries {
+        let entry = entry.map_err(|e| error(e.to_string()))?;
+        if entry.path().is_dir() {
+            continue;
+        }
+
+        // Files whose stems collapse onto the same variant are reported when
+        // pairing
+        let stem = entry.path().file_stem().unwrap_or_default().to_string_lossy().into_owned();
+        match syn::parse_str::<syn::Ident>(&stem.to_upper_camel_case()) {
+            Ok(variant) => {
+                files.insert(variant);
+            }
+            Err(_) => errors.push(error(format!(
+                "`{}` does not name a variant, rename it to start with a letter",
+                entry.path().display()
+            ))),
+        }
+    }
+
+    let mut variants = files.iter().map(|ident| parse_quote!(#ident)).collect::<Vec<syn::Variant>>();
+
+    for subdirectory in subdirectories(directory) {
+        let path = directory.join(&subdirectory);
+
+        match syn::parse_str::<syn::Ident>(&subdirectory.to_upper_camel_case()) {
+            Ok(child) if files.contains(&child) => errors.push(error(format!(
+                "`{}` and a file beside it both name `{child}`",
+                path.display()
+            ))),
+            Ok(child) => variants.push(parse_quote!(#child(#module::#child))),
+            Err(_) => errors.push(error(format!(
+                "`{}` does not name an enum, rename it to start with a letter",
+                path.display()
+            ))),
+        }
+    }
+
+    errors.finish().map(|()| variants)
+}
+
+/// Map the code of every locale but the default to the files in its
+/// directory.
+pub fn gather_translations(
+    locales: &Locales,
+    config: &EnumConfig,
+    target: &syn::Ident,
+) -> syn::Result<BTreeMap<String, BTreeMap<String, Asset>>> {
+    let mut errors = Errors::default();
+    let mut registers = BTreeMap::new();
+
+    for (code, _) in locales.codes.iter().filter(|(code, _)| *code != locales.default) {
+        match gather_assets(config, &config.location.join(code), &format!("{code}/"), target) {
+            Ok(register) => {
+                registers.insert(code.clone(), register);
+            }
+            Err(error) => errors.push(error),
+        }
+    }
+
+    errors.finish().map(|()| registers)
+}
+
+/// Find, per locale and variant, the file named like the default file of the
+/// variant. A translated file must have exactly the keys of the default one,
+/// and files translating no variant are reported unless allowed.
+pub fn pair_translations<'a>(
+    registers: &'a BTreeMap<String, BTreeMap<String, Asset>>,
+    variants: &[Variant],
+    config: &EnumConfig,
+    target: &syn::Ident,
+) -> syn::Result<BTreeMap<&'a str, Vec<Option<&'a Asset>>>> {
+    let mut errors = Errors::default();
+    let mut translations = BTreeMap::new();
+
+    for (code, register) in registers {
+        let mut assets = Vec::new();
+
+        for variant in variants {
+            let default = variant.asset;
+            let file_name = default.path.rsplit_once('/').map_or(default.path.as_str(), |(_, name)| name);
+            let translated = register.get(file_name);
+
+            if let Some(translated) = translated {
+                for key in default.table.keys().filter(|key| !translated.table.contains_key(*key)) {
+                    errors.push(syn::Error::new_spanned(
+                        variant.ident,
+                        format!("`{}` is missing `{key}`, which `{}` has", translated.path, default.path),
+                    ));
+                }
+                for key in translated.table.keys().filter(|key| !default.table.contains_key(*key)) {
+                    errors.push(syn::Error::new_spanned(
+                        variant.ident,
+                        format!("`{}` has `{key}`, which `{}` does not", translated.path, default.path),
+                    ));
+                }
+            }
+
+            assets.push(translated);
+        }
+
+        if !config.allow_extra_files {
+            let extra_assets = register
+                .values()
+                .filter(|asset| !assets.iter().flatten().any(|translated| std::ptr::eq(*translated, *asset)))
+                .map(|asset| asset.path.as_str())
+                .collect::<Vec<_>>();
+
+            if !extra_assets.is_empty() {
+                errors.push(syn::Error::new_spanned(
+                    target,
+                    format!(
+                        "No variant matches the files: {}. Add a variant for each or use #[wheatley(allow_extra_files)]",
+                        extra_assets.join(", ")
+                    ),
+                ));
+            }
+        }
+
+        translations.insert(code.as_str(), assets);
+    }
+
+    errors.finish().map(|()| translations)
+}
+
+/// Map the file and line of every row in `csv` to the row. Rows sharing a
+/// snake_case key are reported against `target`.
+pub fn gather_rows(csv: &Path, key: &str, target: &syn::Ident) -> syn::Result<BTreeMap<String, Asset>> {
+    let file_name = csv.file_name().unwrap_or_default().to_string_lossy();
+    let error = |reason: String| syn::Error::new_spanned(target, format!("Cannot read `{file_name}`: {reason}"));
+
+    let contents = fs::read_to_string(csv).map_err(|e| error(e.to_string()))?;
+    let rows = formats::read_csv(&contents, key).map_err(error)?;
+
+    let mut errors = Errors::default();
+    let mut asset_register: BTreeMap<String, Asset> = BTreeMap::new();
+    let mut stems: BTreeMap<String, String> = BTreeMap::new();
+
+    for row in rows {
+        let stem = row.key.to_snake_case();
+        let path = format!("{file_name}:{}", row.line);
+
+        if let Some(other) = stems.insert(stem.clone(), path.clone()) {
+            errors.push(syn::Error::new_spanned(
+                target,
+                format!("`{other}` and `{path}` both back `{stem}`"),
+            ));
+            continue;
+        }
+
+        let text = row.text;
+        asset_register.insert(
+            path.clone(),
+            Asset {
+                stem,
+                name: row.key,
+                path,
+                contents: quote!(#text),
+                bytes: quote!(#text.as_bytes()),
+                utf8: true,
+                body: None,
+                table: row.table,
+            },
+        );
+    }
+
+    errors.finish().map(|()| asset_register)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn asset_register(paths: &[&str]) -> BTreeMap<String, Asset> {
+        paths
+            .iter()
+            .map(|path| {
+                let stem = Path::new(path).file_stem().unwrap().to_string_lossy().into_owned();
+                let asset = Asset {
+                    stem: stem.clone(),
+                    name: stem,
+                    path: (*path).to_owned(),
+                    contents: TokenStream::new(),
+                    bytes: TokenStream::new(),
+                    utf8: true,
+                    body: None,
+                    table: Table::new(),
+                };
+
+                ((*path).to_owned(), asset)
+            })
+            .collect()
+    }
+
+    fn config(allow_extra_files: bool) -> EnumConfig {
+        EnumConfig {
+            location: PathBuf::from("cars"),
+            allow_extra_files,
+            raw: false,
+            schema: None,
+            format: None,
+            csv: None,
+            default_locale: None,
+            templates: Vec::new(),
+            vis: parse_quote!(pub),
+            renames: Vec::new(),
+        }
+    }
+
+    fn pair(ast: &DeriveInput, paths: &[&str], allow_extra_files: bool) -> syn::Result<Vec<String>> {
+        let syn::Data::Enum(target_enum) = &ast.data else {
+            unreachable!()
+        };
+        let asset_register = asset_register(paths);
+
+        pair_variants(ast, target_enum, &asset_register, &config(allow_extra_files))
+            .map(|(variants, _)| variants.into_iter().map(|variant| variant.asset.path.clone()).collect())
+    }
+
+    #[test]
+    fn pair_each_variant_with_its_file() {
+        let ast: DeriveInput = parse_quote! {
+            enum Cars { Civic, ModelY }
+        };
+
+        let paths = pair(&ast, &["civic.toml", "model_y.toml"], false).unwrap();
+
+        assert_eq!(paths, ["civic.toml", "model_y.toml"]);
+    }
+
+    #[test]
+    fn report_every_variant_without_file() {
+        let ast: DeriveInput = parse_quote! {
+            enum Cars { Civic, Corolla, Forte }
+        };
+
+        let error = pair(&ast, &["civic.toml"], false).unwrap_err();
+        let messages = error.into_iter().map(|e| e.to_string()).collect::<Vec<_>>();
+
+        assert_eq!(messages.len(), 2);
+        assert!(messages[0].contains("`Corolla`"));
+        assert!(messages[1].contains("`Forte`"));
+    }
+
+    #[test]
+    fn report_files_without_variant() {
+        let ast: DeriveInput = parse_quote! {
+            enum Cars { Civic }
+        };
+
+        let error = pair(&ast, &["civic.toml", "forte.toml", "model_y.toml"], false).unwrap_err();
+
+        assert!(error.to_string().contains("forte.toml, model_y.toml"));
+    }
+
+    #[test]
+    fn allow_extra_files() {
+        let ast: DeriveInput = parse_quote! {
+            enum Cars { Civic }
+        };
+
+        assert!(pair(&ast, &["civic.toml", "forte.toml"], true).is_ok());
+    }
+
+    #[test]
+    fn reject_variant_with_fields() {
+        let ast: DeriveInput = parse_quote! {
+            enum Cars { Civic(u8) }
+        };
+
+        assert!(pair(&ast, &["civic.toml"], false).is_err());
+    }
+
+    #[test]
+    fn report_nested_enum_from_another_module() {
+        let ast: DeriveInput = parse_quote! {
+            enum Cars { Trucks(Trucks) }
+        };
+
+        let error = pair(&ast, &[], false).unwrap_err();
+
+        assert!(error.to_string().contains("`cars::Trucks`"));
+    }
+
+    #[test]
+    fn pair_variant_with_named_file() {
+        let ast: DeriveInput = parse_quote! {
+            enum Languages {
+                #[wheatley(file = "c++.toml")]
+                Cpp,
+                Rust,
+            }
+        };
+
+        let paths = pair(&ast, &["c++.toml", "rust.toml"], false).unwrap();
+
+        assert_eq!(paths, ["c++.toml", "rust.toml"]);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn report_broken_symlink() {
+        let directory = tempfile::tempdir().unwrap();
+        fs::write(directory.path().join("civic.toml"), "").unwrap();
+        std::os::unix::fs::symlink(directory.path().join("gone.toml"), directory.path().join("forte.toml")).unwrap();
+        let target: syn::Ident = parse_quote!(Cars);
+
+        let error = gather_assets(&config(false), directory.path(), "", &target).err().unwrap();
+
+        assert!(error.to_string().starts_with("Cannot read `forte.toml`: "));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn pair_symlink_by_its_own_name() {
+        let directory = tempfile::tempdir().unwrap();
+        fs::create_dir(directory.path().join("shared")).unwrap();
+        let base = directory.path().join("shared/base.toml");
+        fs::write(&base, "").unwrap();
+        std::os::unix::fs::symlink(&base, directory.path().join("sedan.toml")).unwrap();
+        let target: syn::Ident = parse_quote!(Cars);
+
+        let asset_register = gather_assets(&config(false), directory.path(), "", &target).unwrap();
+
+        assert_eq!(asset_register["sedan.toml"].stem, "sedan");
+    }
+
+    #[test]
+    fn report_missing_named_file() {
+        let ast: DeriveInput = parse_quote! {
+            enum Languages {
+                #[wheatley(file = "c#.toml")]
+                CSharp,
+            }
+        };
+
+        let error = pair(&ast, &["c#.json"], true).unwrap_err();
+
+        assert!(error.to_string().contains("No file named `c#.toml`"));
+    }
+
+    #[test]
+    fn report_files_sharing_a_stem() {
+        let ast: DeriveInput = parse_quote! {
+            enum Cars { Civic }
+        };
+
+        let error = pair(&ast, &["civic.json", "civic.toml"], false).unwrap_err();
+
+        assert!(error.to_string().contains("`civic.json` and `civic.toml` both back `Civic`"));
+    }
+
+    #[test]
+    fn report_alias_naming_two_variants() {
+        let ast: DeriveInput = parse_quote! {
+            enum Cars {
+                #[wheatley(alias = "honda")]
+                Civic,
+                #[wheatley(alias = "civic")]
+                Forte,
+            }
+        };
+
+        let error = pair(&ast, &["civic.toml", "forte.toml"], false).unwrap_err();
+
+        assert!(error.to_string().contains("`civic` names both `Civic` and `Forte`"));
+    }
+
+    #[test]
+    fn report_keys_missing_from_translation() {
+        let asset = |path: &str, table: &str| Asset {
+            stem: String::from("not_found"),
+            name: String::from("not_found"),
+            path: path.to_owned(),
+            contents: TokenStream::new(),
+            bytes: TokenStream::new(),
+            utf8: true,
+            body: None,
+            table: table.parse().unwrap(),
+        };
+        let default = asset("en/not_found.toml", "message = \"Not found\"\nstatus = 404");
+        let ident = format_ident!("NotFound");
+        let variants = [Variant {
+            ident: &ident,
+            asset: &default,
+            name: String::from("not_found"),
+            aliases: vec![],
+        }];
+        let registers = BTreeMap::from([(
+            String::from("fr"),
+            BTreeMap::from([(String::from("not_found.toml"), asset("fr/not_found.toml", "status = 404"))]),
+        )]);
+
+        let Err(error) = pair_translations(&registers, &variants, &config(false), &ident) else {
+            panic!("The missing key went unreported")
+        };
+
+        assert_eq!(error.to_string(), "`fr/not_found.toml` is missing `message`, which `en/not_found.toml` has");
+    }
+}
@@ -0,0 +1,437 @@
+use proc_macro2::Span;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+use syn::Ident;
+use toml::{Table, Value};
+
+/// The type of a property value.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Kind {
+    String,
+    Integer,
+    Float,
+    Boolean,
+    Datetime,
+    /// An array with elements of one kind, or `None` while only empty
+    /// arrays have been seen.
+    Array(Option<Box<Kind>>),
+    /// A table and its fields, or `None` when declared without fields.
+    Table(Option<BTreeMap<String, Property>>),
+}
+
+impl Kind {
+    /// The kind of `value`, or `None` for an array mixing kinds.
+    pub fn of(value: &Value) -> Option<Self> {
+        let kind = match value {
+            Value::String(_) => Kind::String,
+            Value::Integer(_) => Kind::Integer,
+            Value::Float(_) => Kind::Float,
+            Value::Boolean(_) => Kind::Boolean,
+            Value::Datetime(_) => Kind::Datetime,
+            Value::Table(table) => {
+                let mut fields = BTreeMap::new();
+                for (key, value) in table {
+                    let kind = Kind::of(value)?;
+                    fields.insert(key.clone(), Property { kind, required: true });
+                }
+
+                Kind::Table(Some(fields))
+            }
+            Value::Array(items) => {
+                let mut element: Option<Kind> = None;
+                for item in items {
+                    let kind = Kind::of(item)?;
+                    element = Some(match element {
+                        None => kind,
+                        Some(element) => element.unify(&kind)?,
+                    });
+                }
+
+                Kind::Array(element.map(Box::new))
+            }
+        };
+
+        Some(kind)
+    }
+
+    /// The kind covering both `self` and `other`, if any. Empty arrays fit
+    /// any array, and tables take the union of their fields with the fields
+    /// missing from either becoming optional.
+    pub fn unify(&self, other: &Kind) -> Option<Kind> {
+        match (self, other) {
+            (Kind::Table(None), Kind::Table(fields)) | (Kind::Table(fields), Kind::Table(None)) => {
+                Some(Kind::Table(fields.clone()))
+            }
+            (Kind::Table(Some(a)), Kind::Table(Some(b))) => {
+                let mut fields = BTreeMap::new();
+                for key in a.keys().chain(b.keys()) {
+                    let field = match (a.get(key), b.get(key)) {
+                        (Some(x), Some(y)) => Property {
+                            kind: x.kind.unify(&y.kind)?,
+                            required: x.required && y.required,
+                        },
+                        (Some(field), None) | (None, Some(field)) => Property {
+                            kind: field.kind.clone(),
+                            required: false,
+                        },
+                        (None, None) => unreachable!(),
+                    };
+                    fields.insert(key.clone(), field);
+                }
+
+                Some(Kind::Table(Some(fields)))
+            }
+            (Kind::Array(None), Kind::Array(element)) | (Kind::Array(element), Kind::Array(None)) => {
+                Some(Kind::Array(element.clone()))
+            }
+            (Kind::Array(Some(a)), Kind::Array(Some(b))) => {
+                a.unify(b).map(|element| Kind::Array(Some(Box::new(element))))
+            }
+            (a, b) if a == b => Some(a.clone()),
+            _ => None,
+        }
+    }
+
+    /// Read a declared kind: a type name such as `"integer"`, or an array
+    /// holding one, such as `["string"]`.
+    fn parse(declaration: &Value) -> Option<Self> {
+        match declaration {
+            Value::String(name) => match name.as_str() {
+                "string" => Some(Kind::String),
+                "integer" => Some(Kind::Integer),
+                "float" => Some(Kind::Float),
+                "boolean" => Some(Kind::Boolean),
+                "datetime" => Some(Kind::Datetime),
+                "table" => Some(Kind::Table(None)),
+                _ => None,
+            },
+            Value::Array(element) if element.len() == 1 => {
+                Kind::parse(&element[0]).map(|element| Kind::Array(Some(Box::new(element))))
+            }
+            _ => None,
+        }
+    }
+
+    fn plural(&self) -> String {
+        match self {
+            Kind::String => String::from("strings"),
+            Kind::Integer => String::from("integers"),
+            Kind::Float => String::from("floats"),
+            Kind::Boolean => String::from("booleans"),
+            Kind::Datetime => String::from("datetimes"),
+            Kind::Array(None) => String::from("empty arrays"),
+            Kind::Array(Some(element)) => format!("arrays of {}", element.plural()),
+            Kind::Table(_) => String::from("tables"),
+        }
+    }
+}
+
+impl fmt::Display for Kind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Kind::String => f.write_str("a string"),
+            Kind::Integer => f.write_str("an integer"),
+            Kind::Float => f.write_str("a float"),
+            Kind::Boolean => f.write_str("a boolean"),
+            Kind::Datetime => f.write_str("a datetime"),
+            Kind::Array(None) => f.write_str("an empty array"),
+            Kind::Array(Some(element)) => write!(f, "an array of {}", element.plural()),
+            Kind::Table(None) => f.write_str("a table"),
+            Kind::Table(Some(fields)) => {
+                f.write_str("a table {")?;
+                for (index, (key, field)) in fields.iter().enumerate() {
+                    let separator = if index == 0 { " " } else { ", " };
+                    let optional = if field.required { "" } else { "optional " };
+
+                    write!(f, "{separator}{key}: {optional}{}", field.kind)?;
+                }
+                f.write_str(" }")
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Property {
+    pub kind: Kind,
+    pub required: bool,
+}
+
+/// A file taking part in the schema: the variant it backs, its path
+/// relative to the location and its parsed contents.
+pub struct Source<'a> {
+    pub variant: &'a Ident,
+    pub path: &'a str,
+    pub table: &'a Table,
+}
+
+/// The properties shared by the files behind an enum.
+#[derive(Debug, Default, PartialEq)]
+pub struct Schema {
+    pub properties: BTreeMap<String, Property>,
+}
+
+impl Schema {
+    /// The union of the keys of every file. A key holding different types
+    /// in different files is an error, and a key missing from some files is
+    /// optional.
+    pub fn infer(sources: &[Source]) -> syn::Result<Self> {
+        let mut errors = Errors::default();
+        let mut owners: BTreeMap<&str, (Kind, &str)> = BTreeMap::new();
+
+        for source in sources {
+            for (key, value) in source.table {
+                let Some(kind) = Kind::of(value) else {
+                    errors.push(mixed_array(source, key));
+                    continue;
+                };
+
+                match owners.get_mut(key.as_str()) {
+                    Some((first_kind, first_path)) => match first_kind.unify(&kind) {
+                        Some(unified) => *first_kind = unified,
+                        None => errors.push(syn::Error::new_spanned(
+                            source.variant,
+                            format!(
+                                "`{}` gives `{key}` {kind} but `{first_path}` gives it {first_kind}",
+                                source.path
+                            ),
+                        )),
+                    },
+                    None => {
+                        owners.insert(key, (kind, source.path));
+                    }
+                }
+            }
+        }
+
+        errors.finish()?;
+
+        let properties = owners
+            .into_iter()
+            .map(|(key, (kind, _))| {
+                let required = sources.iter().all(|source| source.table.contains_key(key));
+
+                (key.to_owned(), Property { kind, required })
+            })
+            .collect();
+
+        Ok(Self { properties })
+    }
+
+    /// Read a schema file declaring property types under `[required]` and
+    /// `[optional]`, e.g. `make = "string"`.
+    pub fn load(path: &Path, span: Span) -> syn::Result<Self> {
+        let error = |message: String| syn::Error::new(span, message);
+
+        let table = fs::read_to_string(path)
+            .map_err(|e| error(format!("Cannot read schema {}: {e}", path.display())))?
+            .parse::<Table>()
+            .map_err(|e| error(format!("Cannot parse schema {}: {e}", path.display())))?;
+
+        let mut properties = BTreeMap::new();
+
+        for (section, section_value) in table {
+            let required = match section.as_str() {
+                "required" => true,
+                "optional" => false,
+                _ => return Err(error(format!("Unknown schema section `{section}`, expected `required` or `optional`"))),
+            };
+
+            let Value::Table(declarations) = section_value else {
+                return Err(error(format!("Schema section `{section}` must be a table")));
+            };
+
+            for (key, kind) in declarations {
+                let kind = Kind::parse(&kind)
+                    .ok_or_else(|| error(format!("Property `{key}` has an unknown type, expected one of string, integer, float, boolean, datetime or table, or one of those in an array such as [\"string\"]")))?;
+
+                if properties.insert(key.clone(), Property { kind, required }).is_some() {
+                    return Err(error(format!("Property `{key}` is declared twice")));
+                }
+            }
+        }
+
+        Ok(Self { properties })
+    }
+
+    /// Confirm every file provides the required properties, declares no
+    /// others and uses the declared types. Tables declared without fields
+    /// take theirs from the files.
+    pub fn check(&mut self, sources: &[Source]) -> syn::Result<()> {
+        let mut errors = Errors::default();
+
+        for source in sources {
+            let mut fail = |message: String| errors.push(syn::Error::new_spanned(source.variant, message));
+
+            for (key, property) in self.properties.iter_mut() {
+                let Some(value) = source.table.get(key) else {
+                    if property.required {
+                        fail(format!("`{}` is missing the required property `{key}`", source.path));
+                    }
+                    continue;
+                };
+
+                let Some(kind) = Kind::of(value) else {
+                    fail(format!("`{}` gives `{key}` an array mixing types", source.path));
+                    continue;
+                };
+
+                match property.kind.unify(&kind) {
+                    Some(unified) => property.kind = unified,
+                    None => fail(format!(
+                        "`{}` gives `{key}` {kind} but the schema declares {}",
+                        source.path, property.kind
+                    )),
+                }
+            }
+
+            for key in source.table.keys().filter(|key| !self.properties.contains_key(*key)) {
+                fail(format!("`{}` has the property `{key}` which the schema does not declare", source.path));
+            }
+        }
+
+        errors.finish()
+    }
+}
+
+fn mixed_array(source: &Source, key: &str) -> syn::Error {
+    syn::Error::new_spanned(
+        source.variant,
+        format!("`{}` gives `{key}` an array mixing types", source.path),
+    )
+}
+
+/// Collects errors so they are all reported at once.
+#[derive(Default)]
+pub struct Errors(Option<syn::Error>);
+
+impl Errors {
+    pub fn push(&mut self, error: syn::Error) {
+        match &mut self.0 {
+            Some(errors) => errors.combine(error),
+            None => self.0 = Some(error),
+        }
+    }
+
+    pub fn finish(self) -> syn::Result<()> {
+        self.0.map_or(Ok(()), Err)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proc_macro2::Span;
+
+    fn tables(files: &[&str]) -> Vec<(Ident, String, Table)> {
+        files
+            .iter()
+            .enumerate()
+            .map(|(index, contents)| {
+                (
+                    Ident::new(&format!("Variant{index}"), Span::call_site()),
+                    format!("file_{index}.toml"),
+                    contents.parse::<Table>().unwrap(),
+                )
+            })
+            .collect()
+    }
+
+    fn sources(tables: &[(Ident, String, Table)]) -> Vec<Source<'_>> {
+        tables
+            .iter()
+            .map(|(variant, path, table)| Source { variant, path, table })
+            .collect()
+    }
+
+    #[test]
+    fn infer_union_of_keys() {
+        let tables = tables(&["make = 'Honda'\nyear = 2020", "make = 'Kia'"]);
+
+        let schema = Schema::infer(&sources(&tables)).unwrap();
+
+        assert_eq!(
+            schema.properties["make"],
+            Property { kind: Kind::String, required: true }
+        );
+        assert_eq!(
+            schema.properties["year"],
+            Property { kind: Kind::Integer, required: false }
+        );
+    }
+
+    #[test]
+    fn reject_inconsistent_types() {
+        let tables = tables(&["year = 2020", "year = '2021'"]);
+
+        let error = Schema::infer(&sources(&tables)).unwrap_err().to_string();
+
+        assert!(error.contains("`file_1.toml` gives `year` a string"));
+        assert!(error.contains("`file_0.toml` gives it an integer"));
+    }
+
+    #[test]
+    fn unify_array_elements() {
+        let tables = tables(&["tags = []", "tags = [['a'], []]"]);
+
+        let schema = Schema::infer(&sources(&tables)).unwrap();
+
+        assert_eq!(
+            schema.properties["tags"].kind.to_string(),
+            "an array of arrays of strings"
+        );
+    }
+
+    #[test]
+    fn merge_table_fields() {
+        let tables = tables(&[
+            "[engine]\ncylinders = 4\nturbo = true",
+            "[engine]\ncylinders = 6",
+        ]);
+
+        let schema = Schema::infer(&sources(&tables)).unwrap();
+
+        assert_eq!(
+            schema.properties["engine"].kind.to_string(),
+            "a table { cylinders: an integer, turbo: optional a boolean }"
+        );
+    }
+
+    #[test]
+    fn reject_mixed_array() {
+        let tables = tables(&["tags = ['a', 1]"]);
+
+        let error = Schema::infer(&sources(&tables)).unwrap_err().to_string();
+
+        assert!(error.contains("`file_0.toml` gives `tags` an array mixing types"));
+    }
+
+    #[test]
+    fn check_against_declared_schema() {
+        let mut schema = Schema {
+            properties: BTreeMap::from([
+                (String::from("make"), Property { kind: Kind::String, required: true }),
+                (String::from("year"), Property { kind: Kind::Integer, required: false }),
+            ]),
+        };
+        let tables = tables(&["make = 'Honda'", "year = 'new'\ncolour = 'red'"]);
+
+        let errors = schema
+            .check(&sources(&tables))
+            .unwrap_err()
+            .into_iter()
+            .map(|error| error.to_string())
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            errors,
+            [
+                "`file_1.toml` is missing the required property `make`",
+                "`file_1.toml` gives `year` a string but the schema declares an integer",
+                "`file_1.toml` has the property `colour` which the schema does not declare",
+            ]
+        );
+    }
+}
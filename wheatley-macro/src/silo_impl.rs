@@ -0,0 +1,114 @@
+use crate::configuration::build_config;
+use crate::{lay_out, tokenize_layout};
+use proc_macro2::TokenStream;
+use quote::quote;
+use std::path::PathBuf;
+use syn::DeriveInput;
+
+/// Embed the files of the location into the derived unit struct, with
+/// associated `get`, `iter` and `len` as well as `wheatley::Silo`.
+pub fn write_silo_impl(ast: DeriveInput) -> syn::Result<TokenStream> {
+    let target = &ast.ident;
+
+    let syn::Data::Struct(target_struct) = &ast.data else {
+        return Err(syn::Error::new_spanned(target, "Only unit structs can derive WheatleySilo"));
+    };
+    if !matches!(target_struct.fields, syn::Fields::Unit) || !ast.generics.params.is_empty() {
+        return Err(syn::Error::new_spanned(
+            target,
+            "Silos are unit structs, e.g. `struct Public;`",
+        ));
+    }
+
+    // The options are those of `embed_assets!`, with `=` in place of `:`
+    let mut options = TokenStream::new();
+    for attribute in ast.attrs.iter().filter(|a| a.path().is_ident("wheatley")) {
+        options.extend(attribute.meta.require_list()?.tokens.clone());
+        options.extend(quote!(,));
+    }
+    if options.is_empty() {
+        return Err(syn::Error::new_spanned(
+            target,
+            "Name the directory to embed with #[wheatley(location = \"...\")]",
+        ));
+    }
+
+    let mut config = build_config(options);
+    // As with the other derives, and rust-embed's `folder`, a relative
+    // location starts at the root of the crate rather than wherever rustc runs
+    if config.location.is_relative() {
+        let manifest_dir = PathBuf::from(std::env::var("CARGO_MANIFEST_DIR").unwrap());
+        config.location = manifest_dir.join(&config.location);
+    }
+    if config.constants.is_some() || config.path.is_some() {
+        return Err(syn::Error::new_spanned(
+            target,
+            "`constants` and `path` apply to embed_assets! and asset! only",
+        ));
+    }
+
+    let (layout, key_type) = lay_out(&config)?;
+    let silo = tokenize_layout(&layout, &key_type);
+
+    Ok(quote! {
+        impl #target {
+            /// The silo holding the files.
+            pub fn silo() -> &'static wheatley::Wheatley<'static, #key_type> {
+                static SILO: wheatley::Wheatley<'static, #key_type> = #silo;
+
+                &SILO
+            }
+
+            pub fn get<Q: ::core::borrow::Borrow<#key_type>>(key: Q) -> ::core::option::Option<wheatley::File<'static>> {
+                Self::silo().get(key)
+            }
+
+            pub fn iter() -> wheatley::Iter<'static, #key_type> {
+                Self::silo().iter()
+            }
+
+            pub fn len() -> usize {
+                Self::silo().len()
+            }
+        }
+
+        impl wheatley::Silo for #target {
+            type Key = #key_type;
+
+            fn get<Q: ::core::borrow::Borrow<#key_type>>(&self, key: Q) -> ::core::option::Option<wheatley::File<'_>> {
+                Self::silo().get(key)
+            }
+
+            fn iter(&self) -> wheatley::Iter<'_, #key_type> {
+                Self::silo().iter()
+            }
+
+            fn len(&self) -> usize {
+                Self::silo().len()
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use syn::parse_quote;
+
+    #[test]
+    fn reject_struct_with_fields() {
+        let ast: DeriveInput = parse_quote! {
+            #[wheatley(location = "examples/books")]
+            struct Public { files: usize }
+        };
+
+        assert!(write_silo_impl(ast).unwrap_err().to_string().contains("unit structs"));
+    }
+
+    #[test]
+    fn require_location() {
+        let ast: DeriveInput = parse_quote!(struct Public;);
+
+        assert!(write_silo_impl(ast).unwrap_err().to_string().contains("location"));
+    }
+}
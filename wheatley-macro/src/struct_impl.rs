@@ -0,0 +1,339 @@
+use crate::formats::Format;
+use crate::schema::{Errors, Kind};
+use crate::values;
+use heck::ToShoutySnakeCase;
+use proc_macro2::{Literal, TokenStream};
+use quote::{format_ident, quote, ToTokens};
+use std::fs;
+use std::path::PathBuf;
+use syn::{DeriveInput, GenericArgument, LitStr, PathArguments, Type};
+use toml::{Table, Value};
+
+/// Options given through `#[wheatley(...)]` on a derived struct.
+struct StructConfig {
+    /// The file, relative to the crate root, and the literal naming it.
+    file: (PathBuf, LitStr),
+    format: Option<Format>,
+}
+
+impl StructConfig {
+    fn from_attributes(ast: &DeriveInput) -> syn::Result<Self> {
+        let mut file = None;
+        let mut format = None;
+
+        for attribute in ast.attrs.iter().filter(|a| a.path().is_ident("wheatley")) {
+            attribute.parse_nested_meta(|meta| {
+                if meta.path.is_ident("file") {
+                    file = Some(meta.value()?.parse::<LitStr>()?);
+                    Ok(())
+                } else if meta.path.is_ident("format") {
+                    let name = meta.value()?.parse::<LitStr>()?;
+                    format = Some(Format::parse(&name.value()).ok_or_else(|| {
+                        syn::Error::new(name.span(), "Unknown format, expected one of toml, json, yaml, ron or markdown")
+                    })?);
+                    Ok(())
+                } else {
+                    Err(meta.error("Unknown wheatley option"))
+                }
+            })?;
+        }
+
+        let file = file.ok_or_else(|| {
+            syn::Error::new_spanned(
+                &ast.ident,
+                "Derived structs embed a single file, e.g. #[wheatley(file = \"config/app.toml\")]",
+            )
+        })?;
+
+        let manifest_dir = PathBuf::from(std::env::var("CARGO_MANIFEST_DIR").unwrap());
+
+        Ok(Self {
+            file: (manifest_dir.join(file.value()), file),
+            format,
+        })
+    }
+}
+
+/// Embed the file named by `#[wheatley(file = "...")]` into a constant
+/// named after the struct, e.g. `APP_CONFIG` for `AppConfig`, checking
+/// that the keys of the file are the fields of the struct.
+pub fn write_struct_impl(ast: DeriveInput) -> syn::Result<TokenStream> {
+    let config = StructConfig::from_attributes(&ast)?;
+    let (path, literal) = &config.file;
+
+    let syn::Data::Struct(target_struct) = &ast.data else {
+        panic!("Can only derive a struct")
+    };
+    let syn::Fields::Named(fields) = &target_struct.fields else {
+        return Err(syn::Error::new_spanned(
+            &ast.ident,
+            "Only structs with named fields can embed a file",
+        ));
+    };
+    if !ast.generics.params.is_empty() {
+        return Err(syn::Error::new_spanned(
+            &ast.generics,
+            "Structs embedding a file cannot be generic",
+        ));
+    }
+
+    let format = config.format.or_else(|| Format::of(path)).ok_or_else(|| {
+        syn::Error::new(
+            literal.span(),
+            "Cannot tell the format of the file, name it with #[wheatley(format = \"...\")]",
+        )
+    })?;
+    let mut table = fs::read_to_string(path)
+        .map_err(|e| e.to_string())
+        .and_then(|contents| format.read(&contents))
+        .map_err(|reason| syn::Error::new(literal.span(), format!("Cannot read `{}`: {reason}", literal.value())))?;
+
+    let mut errors = Errors::default();
+    let mut field_values = Vec::new();
+
+    for field in fields.named.iter() {
+        let ident = field.ident.as_ref().unwrap();
+        let key = ident.to_string();
+
+        match field_value(table.remove(&key).as_ref(), &field.ty) {
+            Ok(value) => field_values.push(quote!(#ident: #value)),
+            Err(reason) => errors.push(syn::Error::new_spanned(
+                &field.ty,
+                format!("`{key}` in `{}` {reason}", literal.value()),
+            )),
+        }
+    }
+
+    if !table.is_empty() {
+        let keys = table.keys().map(String::as_str).collect::<Vec<_>>();
+
+        errors.push(syn::Error::new_spanned(
+            &ast.ident,
+            format!("No field matches the keys of `{}`: {}", literal.value(), keys.join(", ")),
+        ));
+    }
+
+    errors.finish()?;
+
+    let target = &ast.ident;
+    let vis = &ast.vis;
+    let constant = format_ident!("{}", target.to_string().to_shouty_snake_case());
+    let full_path = path.to_string_lossy().into_owned();
+    let file = literal.value();
+
+    Ok(quote! {
+        #[doc = concat!("`", #file, "` as ", stringify!(#target), ".")]
+        #vis const #constant: #target = #target {
+            #(#field_values),*
+        };
+
+        impl #target {
+            #[doc = concat!("`", #file, "` as ", stringify!(#target), ".")]
+            pub const fn load() -> &'static Self {
+                // Rebuild whenever the file changes
+                const _: &str = include_str!(#full_path);
+
+                &#constant
+            }
+        }
+    })
+}
+
+/// A constant expression of type `ty` holding `value`. The error explains
+/// why the value does not fit the type.
+fn field_value(value: Option<&Value>, ty: &Type) -> Result<TokenStream, String> {
+    if let Some(inner) = option_inner(ty) {
+        return Ok(match value {
+            Some(value) => {
+                let value = field_value(Some(value), inner)?;
+                quote!(::core::option::Option::Some(#value))
+            }
+            None => quote!(::core::option::Option::None),
+        });
+    }
+
+    let value = value.ok_or_else(|| String::from("is missing, make the field an `Option` if it may be left out"))?;
+    let mismatch = || {
+        format!(
+            "is {}, which does not fit `{}`",
+            Kind::of(value).map_or(String::from("a mixed array"), |kind| kind.to_string()),
+            ty.to_token_stream()
+        )
+    };
+
+    match ty {
+        Type::Reference(reference) => match (&*reference.elem, value) {
+            (Type::Path(path), Value::String(string)) if path.path.is_ident("str") => Ok(quote!(#string)),
+            (Type::Slice(slice), Value::Array(items)) => {
+                let items = items
+                    .iter()
+                    .map(|item| field_value(Some(item), &slice.elem))
+                    .collect::<Result<Vec<_>, _>>()?;
+
+                Ok(quote!(&[#(#items),*]))
+            }
+            _ => Err(mismatch()),
+        },
+        Type::Path(path) => {
+            let name = path.path.segments.last().map(|segment| segment.ident.to_string()).unwrap_or_default();
+
+            match (name.as_str(), value) {
+                ("bool", Value::Boolean(boolean)) => Ok(quote!(#boolean)),
+                ("char", Value::String(string)) if string.chars().count() == 1 => {
+                    let character = string.chars().next().unwrap();
+                    Ok(quote!(#character))
+                }
+                ("f32" | "f64", Value::Float(float)) => Ok(float_literal(*float, &name)),
+                ("f32" | "f64", Value::Integer(integer)) => Ok(float_literal(*integer as f64, &name)),
+                ("Datetime", Value::Datetime(datetime)) => Ok(values::datetime_literal(datetime)),
+                (_, Value::Integer(integer)) if integer_range(&name).is_some() => {
+                    let (min, max) = integer_range(&name).unwrap();
+                    if (min..=max).contains(&i128::from(*integer)) {
+                        Ok(Literal::i64_unsuffixed(*integer).into_token_stream())
+                    } else {
+                        Err(format!("is {integer}, which is out of range for `{name}`"))
+                    }
+                }
+                (_, Value::Table(table)) if integer_range(&name).is_none() && !is_primitive(&name) => {
+                    struct_value(table, ty)
+                }
+                _ => Err(mismatch()),
+            }
+        }
+        _ => Err(format!("has the type `{}`, which cannot be embedded", ty.to_token_stream())),
+    }
+}
+
+/// Fill a struct the derive knows nothing more of than its name. Values
+/// keep their literal types, leaving the compiler to check them against the
+/// fields.
+fn struct_value(table: &Table, ty: &Type) -> Result<TokenStream, String> {
+    let fields = table
+        .iter()
+        .map(|(key, value)| {
+            let value = untyped_value(value).ok_or_else(|| format!("holds the nested table `{key}`, which cannot be embedded"))?;
+            let key = format_ident!("{key}");
+
+            Ok(quote!(#key: #value))
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+
+    Ok(quote!(#ty { #(#fields),* }))
+}
+
+fn untyped_value(value: &Value) -> Option<TokenStream> {
+    Some(match value {
+        Value::String(string) => quote!(#string),
+        Value::Integer(integer) => Literal::i64_unsuffixed(*integer).into_token_stream(),
+        Value::Float(float) => Literal::f64_unsuffixed(*float).into_token_stream(),
+        Value::Boolean(boolean) => quote!(#boolean),
+        Value::Datetime(datetime) => values::datetime_literal(datetime),
+        Value::Array(items) => {
+            let items = items.iter().map(untyped_value).collect::<Option<Vec<_>>>()?;
+            quote!(&[#(#items),*])
+        }
+        Value::Table(_) => return None,
+    })
+}
+
+fn float_literal(float: f64, name: &str) -> TokenStream {
+    let ty = format_ident!("{name}");
+
+    if float.is_nan() {
+        quote!(#ty::NAN)
+    } else if float.is_infinite() && float > 0.0 {
+        quote!(#ty::INFINITY)
+    } else if float.is_infinite() {
+        quote!(#ty::NEG_INFINITY)
+    } else {
+        Literal::f64_unsuffixed(float).into_token_stream()
+    }
+}
+
+/// The values the integer type `name` holds.
+fn integer_range(name: &str) -> Option<(i128, i128)> {
+    Some(match name {
+        "u8" => (0, u8::MAX.into()),
+        "u16" => (0, u16::MAX.into()),
+        "u32" => (0, u32::MAX.into()),
+        "u64" => (0, u64::MAX.into()),
+        "usize" => (0, u64::MAX.into()),
+        "i8" => (i8::MIN.into(), i8::MAX.into()),
+        "i16" => (i16::MIN.into(), i16::MAX.into()),
+        "i32" => (i32::MIN.into(), i32::MAX.into()),
+        "i64" | "isize" => (i64::MIN.into(), i64::MAX.into()),
+        _ => return None,
+    })
+}
+
+fn is_primitive(name: &str) -> bool {
+    matches!(name, "bool" | "char" | "f32" | "f64" | "str" | "Datetime")
+}
+
+/// `T` if `ty` is `Option<T>`.
+fn option_inner(ty: &Type) -> Option<&Type> {
+    let Type::Path(path) = ty else { return None };
+    let segment = path.path.segments.last()?;
+    if segment.ident != "Option" {
+        return None;
+    }
+    let PathArguments::AngleBracketed(arguments) = &segment.arguments else {
+        return None;
+    };
+
+    match arguments.args.first()? {
+        GenericArgument::Type(inner) => Some(inner),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use syn::parse_quote;
+
+    fn embed(value: &str, ty: Type) -> Result<String, String> {
+        let value = format!("value = {value}").parse::<Table>().unwrap().remove("value").unwrap();
+
+        field_value(Some(&value), &ty).map(|tokens| tokens.to_string())
+    }
+
+    #[test]
+    fn embed_integer_into_narrow_type() {
+        assert_eq!(embed("8080", parse_quote!(u16)), Ok(String::from("8080")));
+        assert!(embed("70000", parse_quote!(u16)).unwrap_err().contains("out of range for `u16`"));
+        assert!(embed("-1", parse_quote!(usize)).is_err());
+    }
+
+    #[test]
+    fn embed_array_of_strings() {
+        assert_eq!(
+            embed(r#"["gzip", "zstd"]"#, parse_quote!(&'static [&'static str])),
+            Ok(quote!(&["gzip", "zstd"]).to_string())
+        );
+    }
+
+    #[test]
+    fn embed_optional_field() {
+        assert_eq!(
+            field_value(None, &parse_quote!(Option<u16>)).map(|tokens| tokens.to_string()),
+            Ok(quote!(::core::option::Option::None).to_string())
+        );
+        assert!(field_value(None, &parse_quote!(u16)).unwrap_err().contains("is missing"));
+    }
+
+    #[test]
+    fn embed_table_into_named_struct() {
+        assert_eq!(
+            embed(r#"{ host = "localhost", port = 5432 }"#, parse_quote!(Database)),
+            Ok(quote!(Database { host: "localhost", port: 5432 }).to_string())
+        );
+    }
+
+    #[test]
+    fn reject_mismatched_type() {
+        let reason = embed(r#""8080""#, parse_quote!(u16)).unwrap_err();
+
+        assert_eq!(reason, "is a string, which does not fit `u16`");
+    }
+}
@@ -0,0 +1,111 @@
+use std::collections::BTreeSet;
+use syn::Ident;
+
+/// Names the generated methods already bind: the locale of a localised
+/// enum and the nested enum a variant holds.
+const RESERVED: [&str; 2] = ["inner", "locale"];
+
+/// A string property with `{ident}` placeholders. `{{` and `}}` stand for
+/// literal braces.
+#[derive(Debug, PartialEq)]
+pub struct Template {
+    /// Placeholder names in order of first use.
+    pub placeholders: Vec<String>,
+    /// The template as a `format!` string with named arguments.
+    pub format: String,
+}
+
+impl Template {
+    /// The error explains what in `template` is not a placeholder.
+    pub fn parse(template: &str) -> Result<Self, String> {
+        let mut placeholders: Vec<String> = Vec::new();
+        let mut format = String::new();
+        let mut characters = template.chars().peekable();
+
+        while let Some(character) = characters.next() {
+            match character {
+                '{' if characters.peek() == Some(&'{') => {
+                    characters.next();
+                    format.push_str("{{");
+                }
+                '}' if characters.peek() == Some(&'}') => {
+                    characters.next();
+                    format.push_str("}}");
+                }
+                '{' => {
+                    let mut name = String::new();
+                    loop {
+                        match characters.next() {
+                            Some('}') => break,
+                            Some(character) => name.push(character),
+                            None => return Err(format!("has the unclosed placeholder `{{{name}`")),
+                        }
+                    }
+
+                    if syn::parse_str::<Ident>(&name).is_err() {
+                        return Err(format!(
+                            "has the placeholder `{{{name}}}`, which is not an identifier. Write `{{{{` and `}}}}` for literal braces"
+                        ));
+                    }
+
+                    if RESERVED.contains(&name.as_str()) {
+                        return Err(format!(
+                            "has the placeholder `{{{name}}}`, which the generated method already uses, rename it"
+                        ));
+                    }
+
+                    format.push('{');
+                    format.push_str(&name);
+                    format.push('}');
+                    if !placeholders.contains(&name) {
+                        placeholders.push(name);
+                    }
+                }
+                '}' => return Err(String::from("has a `}` closing no placeholder. Write `}}` for a literal brace")),
+                character => format.push(character),
+            }
+        }
+
+        Ok(Self { placeholders, format })
+    }
+
+    /// The placeholders regardless of order, to compare templates by.
+    pub fn placeholder_set(&self) -> BTreeSet<&str> {
+        self.placeholders.iter().map(String::as_str).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_placeholders_in_order_of_use() {
+        let template = Template::parse("Hello {name}, you have {count} items, {name}").unwrap();
+
+        assert_eq!(template.placeholders, ["name", "count"]);
+        assert_eq!(template.format, "Hello {name}, you have {count} items, {name}");
+    }
+
+    #[test]
+    fn keep_escaped_braces() {
+        let template = Template::parse("{{literal}} {value}").unwrap();
+
+        assert_eq!(template.placeholders, ["value"]);
+        assert_eq!(template.format, "{{literal}} {value}");
+    }
+
+    #[test]
+    fn reject_malformed_placeholders() {
+        assert!(Template::parse("Hello {name").is_err());
+        assert!(Template::parse("Hello name}").is_err());
+        assert!(Template::parse("Hello {first name}").is_err());
+        assert!(Template::parse("Hello {self}").is_err());
+    }
+
+    #[test]
+    fn reject_reserved_placeholders() {
+        assert!(Template::parse("Hello {inner}").unwrap_err().contains("`{inner}`"));
+        assert!(Template::parse("Hello {locale}").unwrap_err().contains("`{locale}`"));
+    }
+}
@@ -0,0 +1,238 @@
+use crate::schema::Kind;
+use heck::{ToSnakeCase, ToUpperCamelCase};
+use proc_macro2::{Literal, TokenStream};
+use quote::{format_ident, quote, ToTokens};
+use syn::{Ident, Visibility};
+use toml::value::{Datetime, Offset};
+use toml::Value;
+
+/// Works out the Rust types properties are embedded as, collecting the
+/// companion structs nested tables need along the way.
+pub struct Embedder {
+    vis: Visibility,
+    pub structs: Vec<TokenStream>,
+}
+
+impl Embedder {
+    /// Companion structs get the visibility `vis` of the derived enum.
+    pub fn new(vis: Visibility) -> Self {
+        Self {
+            vis,
+            structs: Vec::new(),
+        }
+    }
+
+    /// The Rust type properties of `kind` are embedded as. Tables become a
+    /// struct called `name`, such as `CarsEngine`, with its nested tables
+    /// named after their keys in turn. The error explains why a kind cannot
+    /// be embedded.
+    pub fn rust_type(&mut self, kind: &Kind, name: &str) -> Result<TokenStream, String> {
+        let rust_type = match kind {
+            Kind::String => quote!(&'static str),
+            Kind::Integer => quote!(i64),
+            Kind::Float => quote!(f64),
+            Kind::Boolean => quote!(bool),
+            Kind::Datetime => quote!(wheatley::Datetime),
+            Kind::Array(Some(element)) => {
+                let element = self.rust_type(element, name)?;
+
+                quote!(&'static [#element])
+            }
+            Kind::Array(None) => {
+                return Err(String::from(
+                    "is an empty array in every file so its element type is unknown, declare it in a schema",
+                ))
+            }
+            Kind::Table(Some(fields)) => {
+                let ident = format_ident!("{name}");
+
+                let mut field_definitions = Vec::new();
+                for (key, field) in fields {
+                    let field_type = self.rust_type(&field.kind, &nested_name(name, key))?;
+                    let field_type = if field.required {
+                        field_type
+                    } else {
+                        quote!(::core::option::Option<#field_type>)
+                    };
+                    let key = field_ident(key).map_err(|reason| format!("has the field {reason}"))?;
+
+                    field_definitions.push(quote!(pub #key: #field_type));
+                }
+
+                let vis = &self.vis;
+                self.structs.push(quote! {
+                    #[derive(Clone, Copy, Debug, PartialEq)]
+                    #vis struct #ident {
+                        #(#field_definitions),*
+                    }
+                });
+
+                quote!(#ident)
+            }
+            Kind::Table(None) => {
+                return Err(String::from("is a table in no file so its fields are unknown"))
+            }
+        };
+
+        Ok(rust_type)
+    }
+}
+
+fn nested_name(name: &str, key: &str) -> String {
+    format!("{name}{}", key.to_upper_camel_case())
+}
+
+/// The identifier of the method or field holding `key`: the key in
+/// snake_case, escaped as a raw identifier if it is a keyword, e.g. `r#type`.
+/// The error explains why no identifier fits.
+pub fn field_ident(key: &str) -> Result<Ident, String> {
+    let name = key.to_snake_case();
+
+    syn::parse_str::<Ident>(&name)
+        .or_else(|_| syn::parse_str::<Ident>(&format!("r#{name}")))
+        .map_err(|_| format!("`{key}`, which makes no Rust identifier"))
+}
+
+/// A constant expression for `value`, of the type [`Embedder::rust_type`]
+/// gives `kind` under `name`.
+pub fn literal(value: &Value, kind: &Kind, name: &str) -> TokenStream {
+    match (value, kind) {
+        (Value::String(string), _) => quote!(#string),
+        (Value::Integer(integer), _) => Literal::i64_suffixed(*integer).into_token_stream(),
+        (Value::Float(float), _) if float.is_nan() => quote!(f64::NAN),
+        (Value::Float(float), _) if float.is_infinite() && *float > 0.0 => quote!(f64::INFINITY),
+        (Value::Float(float), _) if float.is_infinite() => quote!(f64::NEG_INFINITY),
+        (Value::Float(float), _) => Literal::f64_suffixed(*float).into_token_stream(),
+        (Value::Boolean(boolean), _) => quote!(#boolean),
+        (Value::Datetime(datetime), _) => datetime_literal(datetime),
+        (Value::Array(items), Kind::Array(Some(element))) => {
+            let items = items.iter().map(|item| literal(item, element, name));
+
+            quote!(&[#(#items),*])
+        }
+        (Value::Array(_), _) => quote!(&[]),
+        (Value::Table(table), Kind::Table(Some(fields))) => {
+            let ident = format_ident!("{name}");
+            let fields = fields.iter().map(|(key, field)| {
+                let value = table
+                    .get(key)
+                    .map(|value| literal(value, &field.kind, &nested_name(name, key)));
+                // Checked when the struct was declared
+                let key = field_ident(key).unwrap();
+
+                match (field.required, value) {
+                    (true, value) => quote!(#key: #value),
+                    (false, value) => {
+                        let value = option(value);
+                        quote!(#key: #value)
+                    }
+                }
+            });
+
+            quote!(#ident { #(#fields),* })
+        }
+        (Value::Table(_), _) => unreachable!("Tables without fields are rejected by rust_type"),
+    }
+}
+
+/// A constant `wheatley::Datetime` expression for `datetime`.
+pub fn datetime_literal(datetime: &Datetime) -> TokenStream {
+    let date = option(datetime.date.map(|date| {
+        let (year, month, day) = (date.year, date.month, date.day);
+
+        quote!(wheatley::Date { year: #year, month: #month, day: #day })
+    }));
+
+    let time = option(datetime.time.map(|time| {
+        let (hour, minute, second, nanosecond) = (time.hour, time.minute, time.second, time.nanosecond);
+
+        quote!(wheatley::Time { hour: #hour, minute: #minute, second: #second, nanosecond: #nanosecond })
+    }));
+
+    let offset = option(datetime.offset.map(|offset| match offset {
+        Offset::Z => quote!(wheatley::Offset::Z),
+        Offset::Custom { minutes } => quote!(wheatley::Offset::Custom { minutes: #minutes }),
+    }));
+
+    quote!(wheatley::Datetime { date: #date, time: #time, offset: #offset })
+}
+
+fn option(value: Option<TokenStream>) -> TokenStream {
+    match value {
+        Some(value) => quote!(::core::option::Option::Some(#value)),
+        None => quote!(::core::option::Option::None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(value: &str) -> Value {
+        format!("value = {value}").parse::<toml::Table>().unwrap().remove("value").unwrap()
+    }
+
+    fn embed(value: &str) -> (String, String, Vec<String>) {
+        let value = parse(value);
+        let kind = Kind::of(&value).unwrap();
+        let mut embedder = Embedder::new(Visibility::Inherited);
+
+        let rust_type = embedder.rust_type(&kind, "CarsEngine").unwrap().to_string();
+        let literal = literal(&value, &kind, "CarsEngine").to_string();
+        let structs = embedder.structs.iter().map(ToString::to_string).collect();
+
+        (rust_type, literal, structs)
+    }
+
+    #[test]
+    fn embed_array_of_integers() {
+        let (rust_type, literal, _) = embed("[1, 2]");
+
+        assert_eq!(rust_type, quote!(&'static [i64]).to_string());
+        assert_eq!(literal, quote!(&[1i64, 2i64]).to_string());
+    }
+
+    #[test]
+    fn embed_local_date() {
+        let (_, literal, _) = embed("1979-05-27");
+
+        assert_eq!(
+            literal,
+            quote!(wheatley::Datetime {
+                date: ::core::option::Option::Some(wheatley::Date { year: 1979u16, month: 5u8, day: 27u8 }),
+                time: ::core::option::Option::None,
+                offset: ::core::option::Option::None
+            })
+            .to_string()
+        );
+    }
+
+    #[test]
+    fn embed_nested_tables_as_structs() {
+        let (rust_type, literal, structs) = embed("{ cylinders = 4, turbo = { psi = 14.5 } }");
+
+        assert_eq!(rust_type, "CarsEngine");
+        assert_eq!(
+            literal,
+            quote!(CarsEngine { cylinders: 4i64, turbo: CarsEngineTurbo { psi: 14.5f64 } }).to_string()
+        );
+        assert_eq!(structs.len(), 2);
+        assert!(structs[0].contains("struct CarsEngineTurbo"));
+        assert!(structs[1].contains("struct CarsEngine {"));
+    }
+
+    #[test]
+    fn escape_field_names() {
+        assert_eq!(field_ident("body-style").unwrap(), "body_style");
+        assert_eq!(field_ident("type").unwrap().to_string(), "r#type");
+        assert!(field_ident("self").is_err());
+        assert!(field_ident("3d").is_err());
+    }
+
+    #[test]
+    fn reject_unknown_element_type() {
+        let mut embedder = Embedder::new(Visibility::Inherited);
+
+        assert!(embedder.rust_type(&Kind::Array(None), "CarsTags").is_err());
+    }
+}